@@ -4,8 +4,11 @@ use color_eyre::eyre::Result;
 
 /// # Allocation
 #[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Allocation {
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
     pub end_date: Date<Utc>,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
     pub start_date: Date<Utc>,
 }
 
@@ -97,8 +100,19 @@ impl TimeBound for Allocation {
 #[cfg(test)]
 mod tests {
 
+    use super::*;
+
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn something() {
         assert!(true)
     }
+
+    #[test]
+    fn default_runs_four_weeks() {
+        let a = Allocation::default();
+        assert_eq!(a.duration(), Duration::weeks(4));
+        assert!(a.is_active_on(&a.end_date));
+        assert!(!a.is_active_on(&a.start_date))
+    }
 }
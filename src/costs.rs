@@ -0,0 +1,446 @@
+use crate::{
+    money::{Currency, ExchangeRates, Money, MoneyError},
+    traits::{Breakdown, Contribution, Sample},
+};
+use chrono::prelude::*;
+use rand::Rng;
+
+#[derive(PartialEq, Debug)]
+pub enum CostBuilderError {
+    EndBeforeStart,
+    NegativeAmount,
+    TooLarge,
+    ZeroAmount,
+}
+
+impl std::error::Error for CostBuilderError {}
+impl std::fmt::Display for CostBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            CostBuilderError::EndBeforeStart => write!(f, "Cost ends before it starts."),
+            CostBuilderError::NegativeAmount => {
+                write!(
+                    f,
+                    "Cost amount can't be negative. Costs are always paid out."
+                )
+            }
+            CostBuilderError::TooLarge => write!(f, "Cost amount is too large."),
+            CostBuilderError::ZeroAmount => write!(f, "Cost has no amount."),
+        }
+    }
+}
+
+/// # CostKind
+/// What the money is spent on.
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
+pub enum CostKind {
+    ContractorFee,
+    Licence,
+    Other,
+    Salary,
+}
+
+/// # Recurrence
+/// How often a recurring cost is paid.
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
+pub enum Recurrence {
+    Weekly,
+    /// Paid on the same day of each month, or the month's
+    /// last day when it is shorter.
+    Monthly,
+    Yearly,
+}
+
+impl Recurrence {
+    /// Checks if a payment started on `start` falls due on `date`.
+    fn is_due_on(&self, start: &Date<Utc>, date: &Date<Utc>) -> bool {
+        if date < start {
+            return false;
+        }
+        let months = |d: &Date<Utc>| d.year() * 12 + d.month0() as i32;
+        match self {
+            Recurrence::Weekly => (*date - *start).num_days() % 7 == 0,
+            Recurrence::Monthly => date.day() == start.day().min(days_in_month(date)),
+            Recurrence::Yearly => {
+                (months(date) - months(start)) % 12 == 0
+                    && date.day() == start.day().min(days_in_month(date))
+            }
+        }
+    }
+}
+
+fn days_in_month(date: &Date<Utc>) -> u32 {
+    let first = date.with_day(1).unwrap();
+    let next = match first.month() {
+        12 => Utc.ymd(first.year() + 1, 1, 1),
+        month => Utc.ymd(first.year(), month + 1, 1),
+    };
+    (next - first).num_days() as u32
+}
+
+/// # CostBuilder
+/// Constructs Costs.
+///
+/// Without a recurrence, a cost is paid once on its start date.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(default, deny_unknown_fields))]
+pub struct CostBuilder {
+    /// In units of the currency, to the nearest hundredth.
+    amount: f64,
+    currency: Currency,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::option_date"))]
+    end_date: Option<Date<Utc>>,
+    every: Option<Recurrence>,
+    kind: CostKind,
+    name: String,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
+    start_date: Date<Utc>,
+}
+
+impl Default for CostBuilder {
+    fn default() -> Self {
+        CostBuilder {
+            amount: 1000.0,
+            currency: Currency::default(),
+            end_date: None,
+            every: None,
+            kind: CostKind::Other,
+            name: "New Cost".into(),
+            start_date: Utc::today(),
+        }
+    }
+}
+
+impl CostBuilder {
+    /// This method sets the cost's name.
+    pub fn name(mut self, name: &str) -> CostBuilder {
+        self.name = String::from(name);
+        self
+    }
+
+    /// This method sets the amount paid each time the cost is due,
+    /// in whole units of its currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::costs::CostBuilder;
+    /// let cost = CostBuilder::default()
+    ///   .amount(500)
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(cost.amount().units(), 500)
+    /// ```
+    pub fn amount(mut self, amount: u64) -> CostBuilder {
+        self.amount = amount as f64;
+        self
+    }
+
+    /// This method sets the currency the cost is paid in.
+    pub fn currency(mut self, currency: Currency) -> CostBuilder {
+        self.currency = currency;
+        self
+    }
+
+    /// This method sets what the money is spent on.
+    pub fn kind(mut self, kind: CostKind) -> CostBuilder {
+        self.kind = kind;
+        self
+    }
+
+    /// This method sets the date of the first (or only) payment.
+    pub fn start_date(mut self, date: &Date<Utc>) -> CostBuilder {
+        self.start_date = *date;
+        self
+    }
+
+    /// This method sets the date recurring payments stop.
+    /// Nothing is paid on or after it.
+    pub fn end_date(mut self, date: &Date<Utc>) -> CostBuilder {
+        self.end_date = Some(*date);
+        self
+    }
+
+    /// This method makes the cost recurring.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::costs::{CostBuilder, CostKind, Recurrence};
+    /// use hallo::traits::Contribution;
+    /// use chrono::prelude::*;
+    ///
+    /// let salary = CostBuilder::default()
+    ///   .name("Alice")
+    ///   .kind(CostKind::Salary)
+    ///   .amount(4000)
+    ///   .every(Recurrence::Monthly)
+    ///   .start_date(&Utc.ymd(2022, 8, 31))
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(salary.get_contribution_on(&Utc.ymd(2022, 9, 30)), -400000);
+    /// assert_eq!(salary.get_contribution_on(&Utc.ymd(2022, 10, 1)), 0)
+    /// ```
+    pub fn every(mut self, recurrence: Recurrence) -> CostBuilder {
+        self.every = Some(recurrence);
+        self
+    }
+
+    /// Builds the Cost.
+    /// Use at the end of the call chain.
+    pub fn build(self) -> Result<Cost, CostBuilderError> {
+        let amount = match Money::try_new(self.amount, self.currency) {
+            Ok(amount) if amount.minor() > 0 => amount,
+            Ok(amount) if amount.minor() == 0 => return Err(CostBuilderError::ZeroAmount),
+            Ok(_) => return Err(CostBuilderError::NegativeAmount),
+            Err(_) => return Err(CostBuilderError::TooLarge),
+        };
+        if matches!(self.end_date, Some(end_date) if end_date < self.start_date) {
+            return Err(CostBuilderError::EndBeforeStart);
+        }
+        Ok(Cost {
+            amount,
+            end_date: self.end_date,
+            every: self.every,
+            kind: self.kind,
+            name: self.name,
+            start_date: self.start_date,
+        })
+    }
+}
+
+/// # Cost
+/// Money we're going to spend, once or on a schedule.
+/// Unlike Projects, Costs always happen.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "json",
+    serde(try_from = "CostBuilder", into = "CostBuilder")
+)]
+pub struct Cost {
+    amount: Money,
+    end_date: Option<Date<Utc>>,
+    every: Option<Recurrence>,
+    kind: CostKind,
+    pub name: String,
+    start_date: Date<Utc>,
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<CostBuilder> for Cost {
+    type Error = CostBuilderError;
+
+    fn try_from(builder: CostBuilder) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<Cost> for CostBuilder {
+    fn from(cost: Cost) -> Self {
+        CostBuilder {
+            amount: cost.amount.amount(),
+            currency: cost.amount.currency(),
+            end_date: cost.end_date,
+            every: cost.every,
+            kind: cost.kind,
+            name: cost.name,
+            start_date: cost.start_date,
+        }
+    }
+}
+
+impl Cost {
+    /// Returns the amount paid each time the cost is due.
+    pub fn amount(&self) -> Money {
+        self.amount
+    }
+
+    /// Returns the currency the cost is paid in.
+    pub fn currency(&self) -> Currency {
+        self.amount.currency()
+    }
+
+    /// Returns the Cost with its amount converted to the reporting currency.
+    pub(crate) fn converted(&self, rates: &ExchangeRates) -> Result<Cost, MoneyError> {
+        Ok(Cost {
+            amount: rates.convert(&self.amount)?,
+            ..self.clone()
+        })
+    }
+
+    /// Returns what the money is spent on.
+    pub fn kind(&self) -> CostKind {
+        self.kind
+    }
+
+    /// Returns how often the cost is paid, if it recurs.
+    pub fn every(&self) -> Option<Recurrence> {
+        self.every
+    }
+
+    /// Returns the date of the first (or only) payment.
+    pub fn start_date(&self) -> Date<Utc> {
+        self.start_date
+    }
+
+    /// Returns the date recurring payments stop, if they ever do.
+    pub fn end_date(&self) -> Option<Date<Utc>> {
+        self.end_date
+    }
+
+    /// Checks if a payment is due on a given date.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::costs::{CostBuilder, Recurrence};
+    /// use chrono::prelude::*;
+    ///
+    /// let licence = CostBuilder::default()
+    ///   .every(Recurrence::Weekly)
+    ///   .start_date(&Utc.ymd(2022, 8, 1))
+    ///   .end_date(&Utc.ymd(2022, 8, 15))
+    ///   .build()
+    ///   .unwrap();
+    /// assert!(licence.is_due_on(&Utc.ymd(2022, 8, 8)));
+    /// assert!(!licence.is_due_on(&Utc.ymd(2022, 8, 9)));
+    /// assert!(!licence.is_due_on(&Utc.ymd(2022, 8, 15)))
+    /// ```
+    pub fn is_due_on(&self, date: &Date<Utc>) -> bool {
+        if matches!(self.end_date, Some(end_date) if *date >= end_date) {
+            return false;
+        }
+        match &self.every {
+            Some(recurrence) => recurrence.is_due_on(&self.start_date, date),
+            None => *date == self.start_date,
+        }
+    }
+}
+
+impl std::fmt::Display for Cost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.every, &self.end_date) {
+            (None, _) => write!(f, "{} ({}) -{}", self.name, self.start_date, self.amount()),
+            (Some(every), None) => write!(
+                f,
+                "{} ({} onwards) -{} {:?}",
+                self.name,
+                self.start_date,
+                self.amount(),
+                every
+            ),
+            (Some(every), Some(end_date)) => write!(
+                f,
+                "{} ({} to {}) -{} {:?}",
+                self.name,
+                self.start_date,
+                end_date,
+                self.amount(),
+                every
+            ),
+        }
+    }
+}
+
+impl Contribution for Cost {
+    /// Returns the (negative) amount paid on a given date.
+    ///
+    /// ### Example
+    /// ```
+    /// use hallo::costs::CostBuilder;
+    /// use hallo::traits::Contribution;
+    /// use chrono::prelude::*;
+    ///
+    /// let laptop = CostBuilder::default()
+    ///   .amount(2000)
+    ///   .start_date(&Utc.ymd(2022, 8, 1))
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(laptop.get_contribution_on(&Utc.ymd(2022, 8, 1)), -200000)
+    /// ```
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        match self.is_due_on(date) {
+            true => -self.amount.minor(),
+            false => 0_i64,
+        }
+    }
+
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        Ok(Some(self.amount.currency()))
+    }
+}
+
+impl Breakdown for Cost {
+    fn parts(&self) -> Vec<(&str, &dyn Contribution)> {
+        vec![(&self.name, self)]
+    }
+}
+
+impl Sample for Cost {
+    type Outcome = Cost;
+
+    /// Costs always happen, exactly as planned.
+    fn sample<R: Rng + ?Sized>(&self, _rng: &mut R) -> Self::Outcome {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::Duration;
+
+    fn total(cost: &Cost, from: Date<Utc>, days: i64) -> i64 {
+        (0..days)
+            .map(|day| cost.get_contribution_on(&(from + Duration::days(day))))
+            .sum()
+    }
+
+    #[test]
+    fn builder_rejects_end_before_start() {
+        let c = CostBuilder::default()
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .end_date(&Utc.ymd(2022, 7, 1))
+            .build();
+        assert_eq!(c, Err(CostBuilderError::EndBeforeStart))
+    }
+
+    #[test]
+    fn one_off_is_paid_once() {
+        let c = CostBuilder::default()
+            .amount(300)
+            .start_date(&Utc.ymd(2022, 8, 10))
+            .build()
+            .unwrap();
+        assert_eq!(total(&c, Utc.ymd(2022, 8, 1), 365), -30000)
+    }
+
+    #[test]
+    fn monthly_for_a_year() {
+        let c = CostBuilder::default()
+            .amount(100)
+            .every(Recurrence::Monthly)
+            .start_date(&Utc.ymd(2022, 1, 31))
+            .end_date(&Utc.ymd(2023, 1, 31))
+            .build()
+            .unwrap();
+        assert_eq!(total(&c, Utc.ymd(2022, 1, 1), 800), -120000)
+    }
+
+    #[test]
+    fn yearly_licence() {
+        let c = CostBuilder::default()
+            .amount(100)
+            .kind(CostKind::Licence)
+            .every(Recurrence::Yearly)
+            .start_date(&Utc.ymd(2022, 3, 1))
+            .build()
+            .unwrap();
+        assert_eq!(total(&c, Utc.ymd(2022, 1, 1), 3 * 365), -30000)
+    }
+}
@@ -0,0 +1,256 @@
+use rand::Rng;
+use rand_distr::{Distribution, LogNormal, Pert, Triangular, Uniform};
+
+/// # Estimate
+/// An uncertain quantity, described as a probability distribution.
+/// Note: all values are designed to be approximate.
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
+pub enum Estimate {
+    /// Exactly this value. No uncertainty at all.
+    Fixed(f64),
+    /// Anywhere between `min` and `max`, all equally likely.
+    Uniform { min: f64, max: f64 },
+    /// Three-point estimate, most likely to land on `likely`.
+    Triangular { min: f64, likely: f64, max: f64 },
+    /// Three-point estimate, smoother than `Triangular`
+    /// and more tightly packed around `likely`.
+    Pert { min: f64, likely: f64, max: f64 },
+    /// Never below zero, with a long tail of large values.
+    LogNormal { mean: f64, std_dev: f64 },
+}
+
+impl Estimate {
+    /// Returns the Estimate's expected (mean) value.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    ///
+    /// let e = Estimate::Triangular { min: 1000.0, likely: 2000.0, max: 6000.0 };
+    /// assert_eq!(e.expected(), 3000.0)
+    /// ```
+    pub fn expected(&self) -> f64 {
+        match *self {
+            Estimate::Fixed(value) => value,
+            Estimate::Uniform { min, max } => (min + max) / 2.0,
+            Estimate::Triangular { min, likely, max } => (min + likely + max) / 3.0,
+            Estimate::Pert { min, likely, max } => (min + 4.0 * likely + max) / 6.0,
+            Estimate::LogNormal { mean, .. } => mean,
+        }
+    }
+
+    /// Returns the Estimate with every value multiplied by `factor`,
+    /// like when converting an amount to another currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    ///
+    /// let e = Estimate::Uniform { min: 10.0, max: 20.0 };
+    /// assert_eq!(e.scale(0.5), Estimate::Uniform { min: 5.0, max: 10.0 })
+    /// ```
+    pub fn scale(&self, factor: f64) -> Estimate {
+        match *self {
+            Estimate::Fixed(value) => Estimate::Fixed(value * factor),
+            Estimate::Uniform { min, max } => Estimate::Uniform {
+                min: min * factor,
+                max: max * factor,
+            },
+            Estimate::Triangular { min, likely, max } => Estimate::Triangular {
+                min: min * factor,
+                likely: likely * factor,
+                max: max * factor,
+            },
+            Estimate::Pert { min, likely, max } => Estimate::Pert {
+                min: min * factor,
+                likely: likely * factor,
+                max: max * factor,
+            },
+            Estimate::LogNormal { mean, std_dev } => Estimate::LogNormal {
+                mean: mean * factor,
+                std_dev: std_dev * factor,
+            },
+        }
+    }
+
+    /// Returns the lowest value the Estimate can take.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    ///
+    /// let e = Estimate::Uniform { min: 10.0, max: 20.0 };
+    /// assert_eq!(e.min(), 10.0)
+    /// ```
+    pub fn min(&self) -> f64 {
+        match *self {
+            Estimate::Fixed(value) => value,
+            Estimate::Uniform { min, .. }
+            | Estimate::Triangular { min, .. }
+            | Estimate::Pert { min, .. } => min,
+            Estimate::LogNormal { .. } => 0.0,
+        }
+    }
+
+    /// Returns the highest value the Estimate can take.
+    /// Log-normal estimates have no ceiling.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    ///
+    /// let e = Estimate::Uniform { min: 10.0, max: 20.0 };
+    /// assert_eq!(e.max(), 20.0);
+    /// assert_eq!(Estimate::LogNormal { mean: 10.0, std_dev: 2.0 }.max(), f64::INFINITY)
+    /// ```
+    pub fn max(&self) -> f64 {
+        match *self {
+            Estimate::Fixed(value) => value,
+            Estimate::Uniform { max, .. }
+            | Estimate::Triangular { max, .. }
+            | Estimate::Pert { max, .. } => max,
+            Estimate::LogNormal { .. } => f64::INFINITY,
+        }
+    }
+
+    /// Checks the Estimate describes a real distribution.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    ///
+    /// assert!(Estimate::Uniform { min: 1.0, max: 2.0 }.is_valid());
+    /// assert!(!Estimate::Uniform { min: 2.0, max: 1.0 }.is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Estimate::Fixed(value) => value.is_finite(),
+            Estimate::Uniform { min, max } => min.is_finite() && max.is_finite() && min <= max,
+            Estimate::Triangular { min, likely, max } | Estimate::Pert { min, likely, max } => {
+                min.is_finite() && max.is_finite() && min <= likely && likely <= max
+            }
+            Estimate::LogNormal { mean, std_dev } => {
+                mean.is_finite() && std_dev.is_finite() && mean > 0.0 && std_dev >= 0.0
+            }
+        }
+    }
+
+    /// Draws a single value from the Estimate.
+    /// Invalid estimates always return their expected value.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    ///
+    /// let e = Estimate::Pert { min: 10.0, likely: 12.0, max: 20.0 };
+    /// let value = e.sample(&mut rand::thread_rng());
+    /// assert!((10.0..=20.0).contains(&value))
+    /// ```
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        if !self.is_valid() {
+            return self.expected();
+        }
+        match *self {
+            Estimate::Fixed(value) => value,
+            Estimate::Uniform { min, max } if min < max => Uniform::new(min, max).sample(rng),
+            Estimate::Triangular { min, likely, max } if min < max => {
+                match Triangular::new(min, max, likely) {
+                    Ok(distribution) => distribution.sample(rng),
+                    Err(_) => self.expected(),
+                }
+            }
+            Estimate::Pert { min, likely, max } if min < max => match Pert::new(min, max, likely) {
+                Ok(distribution) => distribution.sample(rng),
+                Err(_) => self.expected(),
+            },
+            Estimate::LogNormal { mean, std_dev } => {
+                match LogNormal::from_mean_cv(mean, std_dev / mean) {
+                    Ok(distribution) => distribution.sample(rng),
+                    Err(_) => self.expected(),
+                }
+            }
+            _ => self.min(),
+        }
+    }
+}
+
+impl From<u32> for Estimate {
+    fn from(value: u32) -> Self {
+        Estimate::Fixed(f64::from(value))
+    }
+}
+
+impl std::fmt::Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Estimate::Fixed(value) => write!(f, "{}", value),
+            Estimate::Uniform { min, max } => write!(f, "{}-{}", min, max),
+            Estimate::Triangular { min, likely, max } | Estimate::Pert { min, likely, max } => {
+                write!(f, "{}-{}-{}", min, likely, max)
+            }
+            Estimate::LogNormal { mean, std_dev } => write!(f, "~{}±{}", mean, std_dev),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn samples_within_range() {
+        let mut rng = rand::thread_rng();
+        let estimates = [
+            Estimate::Uniform { min: 1.0, max: 5.0 },
+            Estimate::Triangular {
+                min: 1.0,
+                likely: 2.0,
+                max: 5.0,
+            },
+            Estimate::Pert {
+                min: 1.0,
+                likely: 4.0,
+                max: 5.0,
+            },
+        ];
+        for e in estimates {
+            assert!((0..100).all(|_| (1.0..=5.0).contains(&e.sample(&mut rng))))
+        }
+    }
+
+    #[test]
+    fn lognormal_is_positive() {
+        let mut rng = rand::thread_rng();
+        let e = Estimate::LogNormal {
+            mean: 100.0,
+            std_dev: 80.0,
+        };
+        assert!((0..100).all(|_| e.sample(&mut rng) > 0.0))
+    }
+
+    #[test]
+    fn degenerate_range() {
+        let mut rng = rand::thread_rng();
+        let e = Estimate::Pert {
+            min: 3.0,
+            likely: 3.0,
+            max: 3.0,
+        };
+        assert!(e.is_valid());
+        assert_eq!(e.sample(&mut rng), 3.0)
+    }
+
+    #[test]
+    fn invalid_likely() {
+        let e = Estimate::Triangular {
+            min: 1.0,
+            likely: 9.0,
+            max: 5.0,
+        };
+        assert!(!e.is_valid());
+        assert_eq!(e.sample(&mut rand::thread_rng()), e.expected())
+    }
+}
@@ -0,0 +1,374 @@
+use crate::projects::Project;
+use crate::timeline::Granularity;
+use crate::traits::TimeBound;
+use chrono::{prelude::*, Duration};
+use std::collections::BTreeMap;
+
+/// # Expert
+/// Someone on the team, or a pool of people sharing the same skills.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Expert {
+    /// Full-time equivalents available each week.
+    pub capacity: f64,
+    pub name: String,
+    pub skills: Vec<String>,
+}
+
+impl Expert {
+    /// Checks if the expert has a given skill.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::expertise::Expert;
+    ///
+    /// let e = Expert { capacity: 1.0, name: "Alice".into(), skills: vec!["rust".into()] };
+    /// assert!(e.has_skill("rust"));
+    /// assert!(!e.has_skill("design"))
+    /// ```
+    pub fn has_skill(&self, skill: &str) -> bool {
+        self.skills.iter().any(|s| s == skill)
+    }
+}
+
+/// # Requirement
+/// How much of a skill a project needs while it's running.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Requirement {
+    /// Full-time equivalents needed each week.
+    pub fte: f64,
+    pub skill: String,
+}
+
+/// # OverAllocation
+/// A week where projects need more of a skill than the team has.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverAllocation {
+    pub capacity: f64,
+    pub demand: f64,
+    /// Names of the projects competing for the skill.
+    pub projects: Vec<String>,
+    pub skill: String,
+    /// Monday of the over-allocated week.
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
+    pub week: Date<Utc>,
+}
+
+impl std::fmt::Display for OverAllocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} w/c {}: {} FTE needed, {} available ({})",
+            self.skill,
+            self.week,
+            self.demand,
+            self.capacity,
+            self.projects.join(", ")
+        )
+    }
+}
+
+/// # Team
+/// Everyone available to work on projects.
+///
+/// People with several skills count towards the capacity of each of them.
+#[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Team {
+    pub members: Vec<Expert>,
+}
+
+impl Team {
+    /// Returns the weekly capacity available for a skill.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::expertise::{Expert, Team};
+    ///
+    /// let team = Team {
+    ///     members: vec![
+    ///         Expert { capacity: 1.0, name: "Alice".into(), skills: vec!["rust".into()] },
+    ///         Expert { capacity: 0.5, name: "Bob".into(), skills: vec!["rust".into()] },
+    ///     ],
+    /// };
+    /// assert_eq!(team.capacity("rust"), 1.5)
+    /// ```
+    pub fn capacity(&self, skill: &str) -> f64 {
+        self.members
+            .iter()
+            .filter(|member| member.has_skill(skill))
+            .map(|member| member.capacity)
+            .sum()
+    }
+
+    /// Finds every week where overlapping projects need more of a skill
+    /// than the team has.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::expertise::{Expert, Team};
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    /// use chrono::prelude::*;
+    ///
+    /// let team = Team {
+    ///     members: vec![Expert { capacity: 1.0, name: "Alice".into(), skills: vec!["rust".into()] }],
+    /// };
+    /// let projects = vec![
+    ///     ProjectBuilder::default().name("p1").requires("rust", 1.0).build().unwrap(),
+    ///     ProjectBuilder::default().name("p2").requires("rust", 0.5).build().unwrap(),
+    /// ];
+    /// assert!(!team.over_allocations(&projects).is_empty());
+    ///
+    /// // Check each trial of a simulation.
+    /// let result = Simulation::new(&projects).trials(10).run().unwrap();
+    /// for trial in &result.trials {
+    ///     let conflicts = team.over_allocations(trial.outcome.iter().flatten());
+    ///     let both_happened = trial.outcome.iter().all(Option::is_some);
+    ///     assert_eq!(conflicts.is_empty(), !both_happened);
+    /// }
+    /// ```
+    pub fn over_allocations<'p>(
+        &self,
+        projects: impl IntoIterator<Item = &'p Project>,
+    ) -> Vec<OverAllocation> {
+        let mut demands: BTreeMap<(String, Date<Utc>), (f64, Vec<String>)> = BTreeMap::new();
+        for project in projects {
+            let allocation = project.allocation();
+            for requirement in project.requirements() {
+                let mut week = Granularity::Week.period_start(&allocation.start_date().succ());
+                while week <= *allocation.end_date() {
+                    let demand = demands
+                        .entry((requirement.skill.clone(), week))
+                        .or_insert_with(|| (0.0, vec![]));
+                    demand.0 += requirement.fte;
+                    demand.1.push(project.name.clone());
+                    week += Duration::weeks(1);
+                }
+            }
+        }
+
+        demands
+            .into_iter()
+            .filter_map(|((skill, week), (demand, projects))| {
+                let capacity = self.capacity(&skill);
+                (demand > capacity + f64::EPSILON).then_some(OverAllocation {
+                    capacity,
+                    demand,
+                    projects,
+                    skill,
+                    week,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the first day from `from` that `project` can start on
+    /// without needing more of any skill than the team has left
+    /// beside the `scheduled` Projects.
+    /// Skills the Project needs more of than the whole team has can't be helped,
+    /// and don't hold it back.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::expertise::{Expert, Team};
+    /// use hallo::projects::ProjectBuilder;
+    ///
+    /// let team = Team {
+    ///     members: vec![Expert { capacity: 1.0, name: "Alice".into(), skills: vec!["rust".into()] }],
+    /// };
+    /// let start = Utc.ymd(2022, 8, 1);
+    /// let p1 = ProjectBuilder::default().start_date(&start).requires("rust", 0.6).build().unwrap();
+    /// let p2 = ProjectBuilder::default().start_date(&start).requires("rust", 0.6).build().unwrap();
+    /// assert_eq!(team.earliest_start(&p2, &start, &[&p1]), Utc.ymd(2022, 8, 29));
+    /// assert_eq!(team.earliest_start(&p2, &start, &[]), start)
+    /// ```
+    pub fn earliest_start(
+        &self,
+        project: &Project,
+        from: &Date<Utc>,
+        scheduled: &[&Project],
+    ) -> Date<Utc> {
+        let duration = project.duration();
+        let mut start = *from;
+        'search: loop {
+            let end = start + duration;
+            for requirement in project.requirements() {
+                let capacity = self.capacity(&requirement.skill);
+                if requirement.fte > capacity + f64::EPSILON {
+                    continue;
+                }
+                let sharing: Vec<(&Project, f64)> = scheduled
+                    .iter()
+                    .filter(|other| *other.start_date() < end && *other.end_date() > start)
+                    .filter_map(|other| {
+                        let fte: f64 = other
+                            .requirements()
+                            .iter()
+                            .filter(|r| r.skill == requirement.skill)
+                            .map(|r| r.fte)
+                            .sum();
+                        (fte > 0.0).then_some((*other, fte))
+                    })
+                    .collect();
+                // Demand only rises when another Project starts,
+                // so those are the days to check.
+                let mut days: Vec<Date<Utc>> = sharing
+                    .iter()
+                    .map(|(other, _)| *other.start_date().max(&start))
+                    .collect();
+                days.sort();
+                for day in days {
+                    let running: Vec<&(&Project, f64)> = sharing
+                        .iter()
+                        .filter(|(other, _)| *other.start_date() <= day && *other.end_date() > day)
+                        .collect();
+                    let demand: f64 =
+                        requirement.fte + running.iter().map(|(_, fte)| fte).sum::<f64>();
+                    if demand > capacity + f64::EPSILON {
+                        // Nothing fits until one of them ends.
+                        start = running
+                            .iter()
+                            .map(|(other, _)| *other.end_date())
+                            .min()
+                            .unwrap_or(start + Duration::days(1));
+                        continue 'search;
+                    }
+                }
+            }
+            return start;
+        }
+    }
+
+    /// Checks if the team has nobody on it.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::projects::ProjectBuilder;
+
+    fn team() -> Team {
+        Team {
+            members: vec![
+                Expert {
+                    capacity: 1.0,
+                    name: "Alice".into(),
+                    skills: vec!["rust".into(), "design".into()],
+                },
+                Expert {
+                    capacity: 1.0,
+                    name: "Bob".into(),
+                    skills: vec!["rust".into()],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn sequential_projects_fit() {
+        let projects = vec![
+            ProjectBuilder::default()
+                .name("p1")
+                .start_date(&Utc.ymd(2022, 8, 1))
+                .duration_weeks(2)
+                .requires("rust", 2.0)
+                .build()
+                .unwrap(),
+            ProjectBuilder::default()
+                .name("p2")
+                .start_date(&Utc.ymd(2022, 8, 22))
+                .duration_weeks(2)
+                .requires("rust", 2.0)
+                .build()
+                .unwrap(),
+        ];
+        assert!(team().over_allocations(&projects).is_empty())
+    }
+
+    #[test]
+    fn overlapping_projects_conflict() {
+        let projects = vec![
+            ProjectBuilder::default()
+                .name("p1")
+                .start_date(&Utc.ymd(2022, 8, 1))
+                .duration_weeks(2)
+                .requires("design", 1.0)
+                .build()
+                .unwrap(),
+            ProjectBuilder::default()
+                .name("p2")
+                .start_date(&Utc.ymd(2022, 8, 8))
+                .duration_weeks(2)
+                .requires("design", 0.5)
+                .build()
+                .unwrap(),
+        ];
+        let conflicts = team().over_allocations(&projects);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].week, Utc.ymd(2022, 8, 8));
+        assert_eq!(conflicts[0].demand, 1.5);
+        assert_eq!(conflicts[0].projects, vec!["p1", "p2"])
+    }
+
+    #[test]
+    fn earliest_start_waits_for_capacity() {
+        let project = |name, month, skill, fte| {
+            ProjectBuilder::default()
+                .name(name)
+                .start_date(&Utc.ymd(2022, month, 1))
+                .duration_weeks(2)
+                .requires(skill, fte)
+                .build()
+                .unwrap()
+        };
+        let team = team();
+        let p1 = project("p1", 8, "rust", 1.5);
+        let p2 = project("p2", 8, "design", 1.0);
+        let p3 = project("p3", 8, "rust", 0.5);
+        // Rust is full until p1 ends, design until p2 ends.
+        assert_eq!(
+            team.earliest_start(
+                &project("p4", 8, "rust", 1.0),
+                &Utc.ymd(2022, 8, 1),
+                &[&p1, &p2, &p3]
+            ),
+            Utc.ymd(2022, 8, 15)
+        );
+        assert_eq!(
+            team.earliest_start(
+                &project("p5", 8, "design", 0.1),
+                &Utc.ymd(2022, 8, 1),
+                &[&p1, &p2, &p3]
+            ),
+            Utc.ymd(2022, 8, 15)
+        );
+        // More than the whole team has can't be helped.
+        assert_eq!(
+            team.earliest_start(
+                &project("p6", 8, "sales", 1.0),
+                &Utc.ymd(2022, 8, 1),
+                &[&p1]
+            ),
+            Utc.ymd(2022, 8, 1)
+        )
+    }
+
+    #[test]
+    fn missing_skill() {
+        let projects = vec![ProjectBuilder::default()
+            .requires("sales", 0.1)
+            .build()
+            .unwrap()];
+        let conflicts = team().over_allocations(&projects);
+        assert!(conflicts.iter().all(|c| c.capacity == 0.0));
+        assert!(!conflicts.is_empty())
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    simulation::SimulationResult,
+    timeline::{Granularity, Period, Timeline},
+    traits::Breakdown,
+};
+use std::io::Write;
+
+#[derive(PartialEq, Debug)]
+pub enum ExportError {
+    Unwritable(String),
+}
+
+impl std::error::Error for ExportError {}
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            ExportError::Unwritable(reason) => write!(f, "CSV can't be written: {}", reason),
+        }
+    }
+}
+
+impl From<csv::Error> for ExportError {
+    fn from(error: csv::Error) -> Self {
+        ExportError::Unwritable(error.to_string())
+    }
+}
+
+/// Each part's contribution in hundredths, as `[part][trial][period]`.
+/// Parts keep the order they're listed in by the outcomes,
+/// even when some only turn up in a few trials.
+struct PartTimelines {
+    names: Vec<String>,
+    values: Vec<Vec<Vec<i64>>>,
+}
+
+impl<O: Breakdown> SimulationResult<O> {
+    /// Writes percentile bands of the net cash flow as CSV, one row per period,
+    /// followed by the average contribution of every Project and Cost.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    /// use hallo::timeline::Granularity;
+    ///
+    /// let projects = vec![ProjectBuilder::default()
+    ///     .name("p1")
+    ///     .probability(1.0)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .build()
+    ///     .unwrap()];
+    /// let result = Simulation::new(&projects)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .end_date(&Utc.ymd(2022, 10, 1))
+    ///     .trials(10)
+    ///     .run().unwrap();
+    /// let mut csv = vec![];
+    /// result.write_bands_csv(Granularity::Month, &mut csv).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    /// let mut lines = csv.lines();
+    /// assert_eq!(lines.next(), Some("period_start,period_end,p10,p50,p90,mean,p1"));
+    /// assert_eq!(
+    ///     lines.next(),
+    ///     Some("2022-08-01,2022-09-01,20000.00,20000.00,20000.00,20000.00,20000.00")
+    /// )
+    /// ```
+    pub fn write_bands_csv<W: Write>(
+        &self,
+        granularity: Granularity,
+        writer: W,
+    ) -> Result<(), ExportError> {
+        let parts = self.part_timelines(granularity);
+        let mut csv = csv::Writer::from_writer(writer);
+
+        let mut header = vec!["period_start", "period_end", "p10", "p50", "p90", "mean"];
+        header.extend(parts.names.iter().map(String::as_str));
+        csv.write_record(&header)?;
+
+        let trials = self.trials.len().max(1) as f64;
+        for (index, (period, summary)) in self.period_summaries(granularity).iter().enumerate() {
+            let mut record = period_cells(period);
+            record.extend(
+                [summary.p10(), summary.p50(), summary.p90(), summary.mean()]
+                    .iter()
+                    .map(|value| format!("{:.2}", value)),
+            );
+            record.extend(parts.values.iter().map(|trials_of_part| {
+                let total: i64 = trials_of_part.iter().map(|trial| trial[index]).sum();
+                format!("{:.2}", total as f64 / 100.0 / trials)
+            }));
+            csv.write_record(&record)?;
+        }
+        csv.flush()
+            .map_err(|e| ExportError::Unwritable(e.to_string()))
+    }
+
+    /// Writes every trial's cash flow as CSV, one row per trial and period,
+    /// followed by the contribution of every Project and Cost.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    /// use hallo::timeline::Granularity;
+    ///
+    /// let projects = vec![ProjectBuilder::default().name("p1").build().unwrap()];
+    /// let result = Simulation::new(&projects)
+    ///     .start_date(&Utc.ymd(2022, 1, 1))
+    ///     .end_date(&Utc.ymd(2023, 1, 1))
+    ///     .trials(10)
+    ///     .run().unwrap();
+    /// let mut csv = vec![];
+    /// result.write_trials_csv(Granularity::Quarter, &mut csv).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    /// assert!(csv.starts_with("trial,period_start,period_end,net,p1\n"));
+    /// assert_eq!(csv.lines().count(), 1 + 10 * 4)
+    /// ```
+    pub fn write_trials_csv<W: Write>(
+        &self,
+        granularity: Granularity,
+        writer: W,
+    ) -> Result<(), ExportError> {
+        let parts = self.part_timelines(granularity);
+        let mut csv = csv::Writer::from_writer(writer);
+
+        let mut header = vec!["trial", "period_start", "period_end", "net"];
+        header.extend(parts.names.iter().map(String::as_str));
+        csv.write_record(&header)?;
+
+        for (trial, timeline) in self.timelines(granularity).iter().enumerate() {
+            for (index, bucket) in timeline.buckets.iter().enumerate() {
+                let mut record = vec![trial.to_string()];
+                record.extend(period_cells(&bucket.period));
+                record.push(units(bucket.value));
+                record.extend(
+                    parts
+                        .values
+                        .iter()
+                        .map(|trials_of_part| units(trials_of_part[trial][index])),
+                );
+                csv.write_record(&record)?;
+            }
+        }
+        csv.flush()
+            .map_err(|e| ExportError::Unwritable(e.to_string()))
+    }
+
+    fn part_timelines(&self, granularity: Granularity) -> PartTimelines {
+        let periods = granularity.periods(&self.start_date, &self.end_date).len();
+        let mut parts = PartTimelines {
+            names: vec![],
+            values: vec![],
+        };
+        for (trial, outcome) in self.trials.iter().map(|t| &t.outcome).enumerate() {
+            let mut next = 0;
+            for (name, part) in outcome.parts() {
+                let index = match parts.names.iter().position(|n| n == name) {
+                    Some(index) => index,
+                    None => {
+                        parts.names.insert(next, name.to_string());
+                        parts
+                            .values
+                            .insert(next, vec![vec![0; periods]; self.trials.len()]);
+                        next
+                    }
+                };
+                next = index + 1;
+                let timeline = Timeline::new(part, &self.start_date, &self.end_date, granularity);
+                for (value, bucket) in parts.values[index][trial].iter_mut().zip(&timeline.buckets)
+                {
+                    *value += bucket.value;
+                }
+            }
+        }
+        parts
+    }
+}
+
+/// Writes an amount in hundredths as whole units, like `12.50`.
+fn units(minor: i64) -> String {
+    let sign = if minor < 0 { "-" } else { "" };
+    let minor = minor.unsigned_abs();
+    format!("{}{}.{:02}", sign, minor / 100, minor % 100)
+}
+
+fn period_cells(period: &Period) -> Vec<String> {
+    vec![
+        period.start_date.naive_utc().to_string(),
+        period.end_date.naive_utc().to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::costs::CostBuilder;
+    use crate::portfolio::Portfolio;
+    use crate::projects::ProjectBuilder;
+    use crate::simulation::Simulation;
+    use chrono::prelude::*;
+
+    fn csv(write: impl FnOnce(&mut Vec<u8>) -> Result<(), ExportError>) -> Vec<Vec<String>> {
+        let mut bytes = vec![];
+        write(&mut bytes).unwrap();
+        String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(|line| line.split(',').map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn parts_add_up_to_net() {
+        let mut portfolio = Portfolio::default();
+        portfolio
+            .add_project(
+                ProjectBuilder::default()
+                    .name("p1")
+                    .start_date(&Utc.ymd(2022, 8, 10))
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        portfolio
+            .add_cost(
+                CostBuilder::default()
+                    .name("laptop")
+                    .start_date(&Utc.ymd(2022, 8, 15))
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let result = Simulation::new(&portfolio)
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .end_date(&Utc.ymd(2022, 10, 1))
+            .trials(20)
+            .run()
+            .unwrap();
+
+        let rows = csv(|w| result.write_trials_csv(Granularity::Month, w));
+        assert_eq!(rows[0][4..], ["p1", "laptop"]);
+        for row in &rows[1..] {
+            let values: Vec<i64> = row[3..]
+                .iter()
+                .map(|v| v.replace('.', "").parse().unwrap())
+                .collect();
+            assert_eq!(values[0], values[1..].iter().sum::<i64>())
+        }
+    }
+
+    #[test]
+    fn bands_have_a_row_per_period() {
+        let projects = vec![ProjectBuilder::default().name("p1").build().unwrap()];
+        let result = Simulation::new(&projects)
+            .start_date(&Utc.ymd(2022, 1, 1))
+            .end_date(&Utc.ymd(2022, 2, 1))
+            .trials(5)
+            .run()
+            .unwrap();
+        let rows = csv(|w| result.write_bands_csv(Granularity::Week, w));
+        assert_eq!(rows.len(), 1 + 6);
+        assert_eq!(rows[1][..2], ["2022-01-01", "2022-01-03"])
+    }
+}
@@ -0,0 +1,570 @@
+use crate::{
+    estimate::Estimate,
+    money::Currency,
+    projects::{Project, ProjectBuilder, ProjectBuilderError, MAX_DAYS},
+};
+use chrono::{prelude::*, Duration};
+use std::io::Read;
+
+#[derive(PartialEq, Debug)]
+pub enum ImportError {
+    /// An amount (`amount`) is marked with another currency than its deal is in (`currency`).
+    ConflictingCurrency {
+        amount: String,
+        currency: Currency,
+    },
+    DuplicateName(String),
+    InvalidAmount(String),
+    InvalidCurrency(String),
+    InvalidDate(String),
+    InvalidDuration(String),
+    InvalidProject(ProjectBuilderError),
+    MissingColumn(String),
+    Unreadable(String),
+    UnknownStage(String),
+}
+
+impl std::error::Error for ImportError {}
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            ImportError::ConflictingCurrency { amount, currency } => {
+                write!(f, "\"{}\" isn't in {}.", amount, currency)
+            }
+            ImportError::DuplicateName(name) => {
+                write!(f, "Another deal is already called \"{}\".", name)
+            }
+            ImportError::InvalidAmount(amount) => write!(f, "\"{}\" isn't an amount.", amount),
+            ImportError::InvalidCurrency(code) => write!(f, "\"{}\" isn't a currency code.", code),
+            ImportError::InvalidDate(date) => write!(f, "\"{}\" isn't a date.", date),
+            ImportError::InvalidDuration(duration) => {
+                write!(f, "\"{}\" isn't a duration.", duration)
+            }
+            ImportError::InvalidProject(error) => write!(f, "{}", error),
+            ImportError::MissingColumn(column) => {
+                write!(f, "There's no \"{}\" column.", column)
+            }
+            ImportError::Unreadable(reason) => write!(f, "CSV can't be read: {}", reason),
+            ImportError::UnknownStage(stage) => {
+                write!(f, "Stage \"{}\" has no probability.", stage)
+            }
+        }
+    }
+}
+
+/// # RowError
+/// Why a single row was skipped.
+#[derive(PartialEq, Debug)]
+pub struct RowError {
+    pub error: ImportError,
+    /// 1-based line in the file, counting the header.
+    pub line: u64,
+}
+
+impl std::fmt::Display for RowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+/// # Import
+/// The Projects read from a CSV file, and the rows that were skipped.
+#[derive(PartialEq, Debug, Default)]
+pub struct Import {
+    pub errors: Vec<RowError>,
+    pub projects: Vec<Project>,
+}
+
+/// # ColumnMapping
+/// Which CSV columns hold each piece of a deal.
+/// Headers are matched ignoring case and surrounding spaces.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ColumnMapping {
+    pub amount: String,
+    pub close_date: String,
+    /// Optional: deals without it are in the import's currency.
+    pub currency: String,
+    /// Optional: deals without it keep the default duration.
+    pub duration: String,
+    pub name: String,
+    pub stage: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        ColumnMapping {
+            amount: "Amount".into(),
+            close_date: "Expected Close Date".into(),
+            currency: "Currency".into(),
+            duration: "Duration".into(),
+            name: "Deal Name".into(),
+            stage: "Stage".into(),
+        }
+    }
+}
+
+/// # CrmImport
+/// Reads a sales pipeline exported from a CRM as CSV.
+///
+/// Each deal becomes a Project starting on its expected close date,
+/// worth its amount, with a probability looked up from its stage.
+/// Rows that can't be read are reported rather than failing the import.
+#[derive(PartialEq, Debug)]
+pub struct CrmImport {
+    columns: ColumnMapping,
+    currency: Currency,
+    date_format: String,
+    decimal_comma: bool,
+    delimiter: u8,
+    stages: Vec<(String, f64)>,
+}
+
+impl Default for CrmImport {
+    fn default() -> Self {
+        CrmImport {
+            columns: ColumnMapping::default(),
+            currency: Currency::default(),
+            date_format: "%Y-%m-%d".into(),
+            decimal_comma: false,
+            delimiter: b',',
+            stages: vec![
+                ("Prospecting".into(), 0.1),
+                ("Qualification".into(), 0.2),
+                ("Proposal".into(), 0.5),
+                ("Negotiation".into(), 0.8),
+                ("Closed Won".into(), 1.0),
+                ("Closed Lost".into(), 0.0),
+            ],
+        }
+    }
+}
+
+impl CrmImport {
+    /// This method sets which columns to read.
+    pub fn columns(mut self, columns: ColumnMapping) -> CrmImport {
+        self.columns = columns;
+        self
+    }
+
+    /// This method sets the currency deals are in, GBP by default.
+    /// A currency column, when there is one, takes precedence.
+    /// Amounts marked with another currency, like `$ 900` or `900 USD`,
+    /// are reported rather than imported in the wrong currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::import::{CrmImport, ImportError};
+    /// use hallo::money::Currency;
+    ///
+    /// let csv = "Deal Name,Amount,Stage,Expected Close Date\nAcme,$900,Proposal,2022-09-01\n";
+    /// let import = CrmImport::default().read(csv.as_bytes()).unwrap();
+    /// assert_eq!(
+    ///     import.errors[0].error,
+    ///     ImportError::ConflictingCurrency { amount: "$900".into(), currency: Currency::GBP }
+    /// );
+    ///
+    /// let import = CrmImport::default().currency(Currency::USD).read(csv.as_bytes()).unwrap();
+    /// assert_eq!(import.projects[0].currency(), Currency::USD)
+    /// ```
+    pub fn currency(mut self, currency: Currency) -> CrmImport {
+        self.currency = currency;
+        self
+    }
+
+    /// This method sets how dates are written, in `chrono` format.
+    pub fn date_format(mut self, format: &str) -> CrmImport {
+        self.date_format = format.into();
+        self
+    }
+
+    /// This method reads amounts written with a decimal comma,
+    /// like `1.500,00`, rather than a decimal point, like `1,500.00`.
+    /// Amounts that don't fit the chosen style are reported, not guessed at.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::import::{CrmImport, ImportError};
+    /// use hallo::money::Currency;
+    ///
+    /// let csv = "Deal Name;Amount;Stage;Expected Close Date\nAcme;€ 1.500,50;Proposal;2022-09-01\n";
+    /// let euros = CrmImport::default().delimiter(b';').currency(Currency::EUR);
+    /// let import = euros.read(csv.as_bytes()).unwrap();
+    /// assert_eq!(import.errors[0].error, ImportError::InvalidAmount("€ 1.500,50".into()));
+    ///
+    /// let import = euros.decimal_comma().read(csv.as_bytes()).unwrap();
+    /// assert_eq!(import.projects[0].value().minor(), 150050)
+    /// ```
+    pub fn decimal_comma(mut self) -> CrmImport {
+        self.decimal_comma = true;
+        self
+    }
+
+    /// This method sets the character between cells, `,` by default.
+    pub fn delimiter(mut self, delimiter: u8) -> CrmImport {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// This method sets the probability of deals in a stage,
+    /// replacing any probability the stage already had.
+    /// Stages are matched ignoring case.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::import::CrmImport;
+    ///
+    /// let csv = "Deal Name,Amount,Stage,Expected Close Date\nAcme,12000,Demo,2022-09-01\n";
+    /// let import = CrmImport::default().stage("Demo", 0.3).read(csv.as_bytes()).unwrap();
+    /// assert_eq!(import.projects[0].probability(), 0.3);
+    /// assert_eq!(import.projects[0].value().units(), 12000)
+    /// ```
+    pub fn stage(mut self, stage: &str, probability: f64) -> CrmImport {
+        self.stages
+            .retain(|(name, _)| !name.eq_ignore_ascii_case(stage));
+        self.stages.push((stage.into(), probability));
+        self
+    }
+
+    /// Reads every deal.
+    /// Fails only when the header is missing a required column.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::import::{CrmImport, ImportError};
+    ///
+    /// let csv = "\
+    /// Deal Name,Amount,Stage,Expected Close Date,Duration
+    /// Acme,5000,Proposal,2022-09-01,6 weeks
+    /// Globex,lots,Proposal,2022-10-01,
+    /// Initech,3000,Negotiation,2022-11-01,30
+    /// ";
+    /// let import = CrmImport::default().read(csv.as_bytes()).unwrap();
+    /// assert_eq!(import.projects.len(), 2);
+    /// assert_eq!(import.errors[0].line, 3);
+    /// assert_eq!(import.errors[0].error, ImportError::InvalidAmount("lots".into()))
+    /// ```
+    pub fn read<R: Read>(&self, reader: R) -> Result<Import, ImportError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .flexible(true)
+            .from_reader(reader);
+        let headers = reader
+            .headers()
+            .map_err(|e| ImportError::Unreadable(e.to_string()))?
+            .clone();
+        let find = |column: &str| {
+            headers
+                .iter()
+                .position(|header| header.trim().eq_ignore_ascii_case(column.trim()))
+        };
+        let required =
+            |column: &str| find(column).ok_or_else(|| ImportError::MissingColumn(column.into()));
+        let columns = Columns {
+            amount: required(&self.columns.amount)?,
+            close_date: required(&self.columns.close_date)?,
+            currency: find(&self.columns.currency),
+            duration: find(&self.columns.duration),
+            name: required(&self.columns.name)?,
+            stage: required(&self.columns.stage)?,
+        };
+
+        let mut import = Import::default();
+        for record in reader.records() {
+            let (line, project) = match record {
+                Ok(record) => {
+                    let line = record.position().map_or(0, |p| p.line());
+                    (line, self.read_row(&record, &columns))
+                }
+                Err(error) => {
+                    let line = error.position().map_or(0, |p| p.line());
+                    (line, Err(ImportError::Unreadable(error.to_string())))
+                }
+            };
+            let project = project.and_then(|project| {
+                if import.projects.iter().any(|p| p.name == project.name) {
+                    return Err(ImportError::DuplicateName(project.name));
+                }
+                Ok(project)
+            });
+            match project {
+                Ok(project) => import.projects.push(project),
+                Err(error) => import.errors.push(RowError { error, line }),
+            }
+        }
+        Ok(import)
+    }
+
+    fn read_row(
+        &self,
+        record: &csv::StringRecord,
+        columns: &Columns,
+    ) -> Result<Project, ImportError> {
+        let cell = |index: usize| record.get(index).unwrap_or_default().trim();
+
+        let stage = cell(columns.stage);
+        let probability = self
+            .stages
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(stage))
+            .map(|(_, probability)| *probability)
+            .ok_or_else(|| ImportError::UnknownStage(stage.into()))?;
+
+        let close_date = cell(columns.close_date);
+        let start_date = NaiveDate::parse_from_str(close_date, &self.date_format)
+            .map(|date| Utc.from_utc_date(&date))
+            .map_err(|_| ImportError::InvalidDate(close_date.into()))?;
+
+        let amount = cell(columns.amount);
+        let value = parse_amount(amount, self.decimal_comma)?;
+        let currency = match columns.currency.map(cell).filter(|c| !c.is_empty()) {
+            Some(code) => {
+                Currency::new(code).map_err(|_| ImportError::InvalidCurrency(code.into()))?
+            }
+            None => self.currency,
+        };
+        if amount_currency(amount)?.is_some_and(|marked| marked != currency) {
+            return Err(ImportError::ConflictingCurrency {
+                amount: amount.into(),
+                currency,
+            });
+        }
+
+        let mut builder = ProjectBuilder::default()
+            .name(cell(columns.name))
+            .start_date(&start_date)
+            .value_estimate(Estimate::Fixed(value))
+            .currency(currency)
+            .probability(probability);
+        if let Some(duration) = columns.duration.map(cell).filter(|d| !d.is_empty()) {
+            builder = builder.duration(&parse_duration(duration)?);
+        }
+        builder.build().map_err(ImportError::InvalidProject)
+    }
+}
+
+/// Column positions found in the header.
+struct Columns {
+    amount: usize,
+    close_date: usize,
+    currency: Option<usize>,
+    duration: Option<usize>,
+    name: usize,
+    stage: usize,
+}
+
+/// Reads an amount like `12500`, `$12,500.00` or `€ 900`,
+/// or `€ 1.500,00` with a decimal comma.
+/// The separator that isn't the decimal one may only group thousands.
+fn parse_amount(text: &str, decimal_comma: bool) -> Result<f64, ImportError> {
+    let invalid = || ImportError::InvalidAmount(text.into());
+    let (decimal, group) = match decimal_comma {
+        true => (',', '.'),
+        false => ('.', ','),
+    };
+    let number: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+        .collect();
+    let (whole, fraction) = number.split_once(decimal).unwrap_or((&number, "0"));
+    let mut groups = whole.split(group);
+    let first = groups.next().unwrap_or_default();
+    if !number.contains(|c: char| c.is_ascii_digit())
+        || fraction.contains([decimal, group])
+        || groups.any(|g| g.len() != 3)
+        || (whole.contains(group) && !first.contains(|c: char| c.is_ascii_digit()))
+    {
+        return Err(invalid());
+    }
+    match format!("{}.{}", whole.replace(group, ""), fraction).parse::<f64>() {
+        Ok(amount) if amount >= 0.0 => Ok(amount),
+        _ => Err(invalid()),
+    }
+}
+
+/// Finds the currency an amount is marked with, if any:
+/// a symbol, like `£`, `€` or `$` (US dollars), or a code, like `EUR`.
+/// Amounts marked more than once must agree.
+fn amount_currency(text: &str) -> Result<Option<Currency>, ImportError> {
+    let invalid = || ImportError::InvalidAmount(text.into());
+    let mut marks = vec![];
+    for c in text.chars() {
+        match c {
+            '£' => marks.push(Currency::GBP),
+            '€' => marks.push(Currency::EUR),
+            '$' => marks.push(Currency::USD),
+            _ => {}
+        }
+    }
+    for word in text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+    {
+        marks.push(Currency::new(word).map_err(|_| invalid())?);
+    }
+    match marks.split_first() {
+        Some((first, rest)) if rest.iter().any(|mark| mark != first) => Err(invalid()),
+        first => Ok(first.map(|(first, _)| *first)),
+    }
+}
+
+/// Reads a duration like `30`, `30 days` or `6 weeks`.
+/// Plain numbers are days.
+fn parse_duration(text: &str) -> Result<Duration, ImportError> {
+    let invalid = || ImportError::InvalidDuration(text.into());
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let count: i64 = text[..split].parse().map_err(|_| invalid())?;
+    let days = match text[split..].trim().to_lowercase().as_str() {
+        "" | "d" | "day" | "days" => count,
+        "w" | "wk" | "wks" | "week" | "weeks" => count.saturating_mul(7),
+        _ => return Err(invalid()),
+    };
+    match days <= MAX_DAYS {
+        true => Ok(Duration::days(days)),
+        false => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::traits::TimeBound;
+
+    #[test]
+    fn custom_columns() {
+        let csv = "Opportunity;Value;Phase;Close\nAcme;1000;won;01/09/2022\n";
+        let columns = ColumnMapping {
+            amount: "Value".into(),
+            close_date: "Close".into(),
+            name: "Opportunity".into(),
+            stage: "Phase".into(),
+            ..ColumnMapping::default()
+        };
+        let import = CrmImport::default()
+            .columns(columns)
+            .date_format("%d/%m/%Y")
+            .delimiter(b';')
+            .stage("Won", 1.0)
+            .read(csv.as_bytes())
+            .unwrap();
+        let project = &import.projects[0];
+        assert_eq!(project.name, "Acme");
+        assert_eq!(project.start_date(), &Utc.ymd(2022, 9, 1));
+        assert_eq!(project.probability(), 1.0)
+    }
+
+    #[test]
+    fn missing_column_fails_the_import() {
+        let csv = "Deal Name,Amount,Stage\nAcme,1000,Proposal\n";
+        assert_eq!(
+            CrmImport::default().read(csv.as_bytes()),
+            Err(ImportError::MissingColumn("Expected Close Date".into()))
+        )
+    }
+
+    #[test]
+    fn amounts_need_a_consistent_style() {
+        let point = |text| parse_amount(text, false).ok();
+        let comma = |text| parse_amount(text, true).ok();
+        assert_eq!(point("$12,500.00"), Some(12500.0));
+        assert_eq!(point("1,500,000"), Some(1_500_000.0));
+        assert_eq!(point("€ 1.500,00"), None);
+        assert_eq!(point("1,50"), None);
+        assert_eq!(point("1.500.000"), None);
+        assert_eq!(comma("€ 1.500,00"), Some(1500.0));
+        assert_eq!(comma("1500,5"), Some(1500.5));
+        assert_eq!(comma("1,500.00"), None);
+        assert_eq!(comma(",500"), Some(0.5));
+        assert_eq!(point(",500"), None);
+        assert_eq!(point("lots"), None);
+        assert_eq!(comma("-"), None)
+    }
+
+    #[test]
+    fn amounts_are_in_their_deal_currency() {
+        let csv = "Deal Name,Amount,Stage,Expected Close Date,Currency\n\
+                   Acme,\"€ 1,500.50\",Proposal,2022-09-01,EUR\n\
+                   Globex,USD 900,Proposal,2022-09-01,\n\
+                   Initech,900,Proposal,2022-09-01,\n\
+                   Hooli,£900,Proposal,2022-09-01,EUR\n\
+                   Umbrella,900,Proposal,2022-09-01,euro\n\
+                   Soylent,$900 CAD,Proposal,2022-09-01,\n";
+        let import = CrmImport::default()
+            .currency(Currency::USD)
+            .read(csv.as_bytes())
+            .unwrap();
+        let deals: Vec<_> = import
+            .projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.value().minor(), p.currency()))
+            .collect();
+        assert_eq!(
+            deals,
+            [
+                ("Acme", 150050, Currency::EUR),
+                ("Globex", 90000, Currency::USD),
+                ("Initech", 90000, Currency::USD),
+            ]
+        );
+        let errors: Vec<_> = import.errors.iter().map(|e| (e.line, &e.error)).collect();
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    5,
+                    &ImportError::ConflictingCurrency {
+                        amount: "£900".into(),
+                        currency: Currency::EUR
+                    }
+                ),
+                (6, &ImportError::InvalidCurrency("euro".into())),
+                (7, &ImportError::InvalidAmount("$900 CAD".into())),
+            ]
+        )
+    }
+
+    #[test]
+    fn durations_are_bounded() {
+        assert_eq!(parse_duration("52 weeks"), Ok(Duration::weeks(52)));
+        for text in [
+            "9223372036854775807",
+            "9223372036854775807 weeks",
+            "100000 days",
+        ] {
+            assert_eq!(
+                parse_duration(text),
+                Err(ImportError::InvalidDuration(text.into()))
+            )
+        }
+    }
+
+    #[test]
+    fn bad_rows_are_reported() {
+        let csv = "Deal Name,Amount,Stage,Expected Close Date,Duration\n\
+                   Acme,1000,Proposal,2022-09-01,2 weeks\n\
+                   Acme,1000,Proposal,2022-09-01,\n\
+                   Globex,1000,Lunch,2022-09-01,\n\
+                   Initech,1000,Proposal,next week,\n\
+                   Hooli,1000,Proposal,2022-09-01,a while\n\
+                   Umbrella,1000,Proposal,2022-09-01,0\n";
+        let import = CrmImport::default().read(csv.as_bytes()).unwrap();
+        assert_eq!(import.projects.len(), 1);
+        assert_eq!(import.projects[0].duration(), Duration::weeks(2));
+        let errors: Vec<_> = import.errors.iter().map(|e| (e.line, &e.error)).collect();
+        assert_eq!(
+            errors,
+            vec![
+                (3, &ImportError::DuplicateName("Acme".into())),
+                (4, &ImportError::UnknownStage("Lunch".into())),
+                (5, &ImportError::InvalidDate("next week".into())),
+                (6, &ImportError::InvalidDuration("a while".into())),
+                (
+                    7,
+                    &ImportError::InvalidProject(ProjectBuilderError::ZeroLengthDuration)
+                ),
+            ]
+        )
+    }
+}
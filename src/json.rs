@@ -0,0 +1,269 @@
+//! JSON in and out, behind the `json` feature.
+//!
+//! Everything is wrapped in a small envelope recording the schema version,
+//! so consumers can tell when the shape of the data changes:
+//!
+//! ```json
+//! { "schema_version": 1, "data": { ... } }
+//! ```
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The version of the JSON schema written by this crate.
+/// Bumped whenever a field is renamed, removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(PartialEq, Debug)]
+pub enum JsonError {
+    Invalid(String),
+    UnsupportedVersion(u32),
+}
+
+impl std::error::Error for JsonError {}
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            JsonError::Invalid(reason) => write!(f, "JSON is invalid: {}", reason),
+            JsonError::UnsupportedVersion(version) => write!(
+                f,
+                "JSON schema version {} isn't supported (expected {}).",
+                version, SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(error: serde_json::Error) -> Self {
+        JsonError::Invalid(error.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    schema_version: u32,
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    schema_version: u32,
+}
+
+/// Writes anything serializable as versioned JSON.
+///
+/// ## Example
+/// ```
+/// use hallo::json::{from_json, to_json};
+/// use hallo::projects::{Project, ProjectBuilder};
+///
+/// let project = ProjectBuilder::default().name("p1").value(1000).build().unwrap();
+/// let json = to_json(&project).unwrap();
+/// assert!(json.starts_with(r#"{"schema_version":1,"data":{"#));
+/// assert_eq!(from_json::<Project>(&json).unwrap(), project)
+/// ```
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, JsonError> {
+    Ok(serde_json::to_string(&Envelope {
+        schema_version: SCHEMA_VERSION,
+        data: value,
+    })?)
+}
+
+/// Same as `to_json`, indented for people to read.
+pub fn to_json_pretty<T: Serialize + ?Sized>(value: &T) -> Result<String, JsonError> {
+    Ok(serde_json::to_string_pretty(&Envelope {
+        schema_version: SCHEMA_VERSION,
+        data: value,
+    })?)
+}
+
+/// Reads versioned JSON written by `to_json`.
+/// Input is validated the same way the builders validate it.
+///
+/// ## Example
+/// ```
+/// use hallo::json::{from_json, JsonError};
+/// use hallo::projects::Project;
+///
+/// let json = r#"{"schema_version":1,"data":{"name":"p1","probability":2.0}}"#;
+/// assert!(matches!(from_json::<Project>(json), Err(JsonError::Invalid(_))));
+///
+/// let json = r#"{"schema_version":99,"data":{"name":"p1"}}"#;
+/// assert_eq!(from_json::<Project>(json), Err(JsonError::UnsupportedVersion(99)))
+/// ```
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, JsonError> {
+    let version: Version = serde_json::from_str(json)?;
+    if version.schema_version != SCHEMA_VERSION {
+        return Err(JsonError::UnsupportedVersion(version.schema_version));
+    }
+    let envelope: Envelope<T> = serde_json::from_str(json)?;
+    Ok(envelope.data)
+}
+
+/// Dates as `2022-09-01`.
+pub(crate) mod date {
+    use chrono::prelude::*;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    const FORMAT: &str = "%Y-%m-%d";
+
+    pub fn serialize<S: Serializer>(date: &Date<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&date.format(FORMAT))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Date<Utc>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        NaiveDate::parse_from_str(&text, FORMAT)
+            .map(|date| Utc.from_utc_date(&date))
+            .map_err(|_| D::Error::custom(format!("\"{}\" isn't a date like 2022-09-01", text)))
+    }
+}
+
+/// Optional dates as `2022-09-01` or `null`.
+pub(crate) mod option_date {
+    use chrono::prelude::*;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        date: &Option<Date<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => super::date::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Date<Utc>>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::date")] Date<Utc>);
+
+        let date = Option::<Wrapper>::deserialize(deserializer)?;
+        Ok(date.map(|Wrapper(date)| date))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::costs::{CostBuilder, Recurrence};
+    use crate::money::{Currency, ExchangeRates};
+    use crate::portfolio::Portfolio;
+    use crate::projects::ProjectBuilder;
+    use crate::simulation::{Simulation, SimulationResult};
+    use crate::traits::Sample;
+    use chrono::prelude::*;
+
+    fn portfolio() -> Portfolio {
+        let mut portfolio = Portfolio::default();
+        portfolio
+            .add_project(
+                ProjectBuilder::default()
+                    .name("p1")
+                    .start_date(&Utc.ymd(2022, 8, 1))
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        portfolio
+            .add_cost(
+                CostBuilder::default()
+                    .name("rent")
+                    .start_date(&Utc.ymd(2022, 8, 1))
+                    .every(Recurrence::Monthly)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        portfolio
+    }
+
+    #[test]
+    fn simulation_results_round_trip() {
+        let portfolio = portfolio();
+        let result = Simulation::new(&portfolio)
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .end_date(&Utc.ymd(2022, 9, 1))
+            .trials(5)
+            .seed(1)
+            .run()
+            .unwrap();
+        let json = to_json(&result).unwrap();
+        assert_eq!(
+            from_json::<SimulationResult<Portfolio>>(&json).unwrap(),
+            result
+        )
+    }
+
+    #[test]
+    fn schema_is_stable() {
+        let value: serde_json::Value =
+            serde_json::from_str(&to_json(&portfolio()).unwrap()).unwrap();
+        let project = &value["data"]["projects"][0];
+        assert_eq!(project["allocation"]["start_date"], "2022-08-01");
+        assert_eq!(project["value"]["fixed"], 20000.0);
+        assert_eq!(project["recognition"], "daily");
+        assert_eq!(project["currency"], "GBP");
+        assert_eq!(value["data"]["exchange_rates"]["reporting"], "GBP");
+        let cost = &value["data"]["costs"][0];
+        assert_eq!(cost["every"], "monthly");
+        assert_eq!(cost["end_date"], serde_json::Value::Null)
+    }
+
+    #[test]
+    fn exchange_rates_round_trip() {
+        let mut rates = ExchangeRates::new(Currency::EUR);
+        rates.add_rate(Currency::GBP, 1.15).unwrap();
+        let mut portfolio = Portfolio::default();
+        portfolio.set_exchange_rates(rates).unwrap();
+        let json = to_json(&portfolio).unwrap();
+        assert!(json.contains(r#""rates":{"GBP":1.15}"#));
+        assert_eq!(from_json::<Portfolio>(&json).unwrap(), portfolio);
+
+        let json = json.replace("1.15", "-1.0");
+        assert!(matches!(
+            from_json::<Portfolio>(&json),
+            Err(JsonError::Invalid(_))
+        ))
+    }
+
+    #[test]
+    fn converted_costs_keep_their_hundredths() {
+        let mut rates = ExchangeRates::new(Currency::GBP);
+        rates.add_rate(Currency::EUR, 0.85).unwrap();
+        let mut portfolio = Portfolio::default();
+        portfolio.set_exchange_rates(rates).unwrap();
+        portfolio
+            .add_cost(
+                CostBuilder::default()
+                    .amount(1001)
+                    .currency(Currency::EUR)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let outcome = portfolio.sample(&mut rand::thread_rng());
+        assert_eq!(outcome.costs()[0].amount().minor(), 85085);
+        let json = to_json(&outcome).unwrap();
+        assert!(json.contains(r#""amount":850.85"#));
+        assert_eq!(from_json::<Portfolio>(&json).unwrap(), outcome);
+
+        let json = json.replace("850.85", "-850.85");
+        assert!(matches!(
+            from_json::<Portfolio>(&json),
+            Err(JsonError::Invalid(_))
+        ))
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let json = r#"{"schema_version":1,"data":{"projects":[{"name":"a"},{"name":"a"}]}}"#;
+        assert!(matches!(
+            from_json::<Portfolio>(json),
+            Err(JsonError::Invalid(_))
+        ))
+    }
+}
@@ -15,13 +15,53 @@
 //! # Features
 //!
 //! - Plan *Projects*, *Expertise* and *Costs*
+//! - Link Projects that depend on, rule out or compete with each other
+//! - Correlate Projects that tend to win or lose together
+//! - Start Projects after others end, or once the team is free
+//! - Push Projects back until the Team has the people for them, and see how late they start
+//! - Keep amounts in their own currencies, reported in one through exchange rates
+//! - Write plans in TOML or YAML files (see `examples/plan.toml`)
+//! - Import sales pipelines from CRM CSV exports
+//! - Read and write versioned JSON with the `json` feature
+//! - Export percentile bands and raw trials as CSV for spreadsheets
+//! - Draw fan charts and histograms right in the terminal
+//! - Write SVG fan charts, Gantt charts and histograms for reports
+//! - See when projects overlap in a Gantt chart, in the terminal or as SVG and HTML
 //!
 //! # Usage
 //!
-//! Coming soon
+//! ```
+//! use hallo::portfolio::Portfolio;
+//! use hallo::projects::ProjectBuilder;
+//! use hallo::simulation::Simulation;
+//!
+//! let mut portfolio = Portfolio::default();
+//! portfolio.add_project(ProjectBuilder::default().name("p1").value(5000).build().unwrap()).unwrap();
+//! portfolio.add_project(ProjectBuilder::default().name("p2").value(1000).build().unwrap()).unwrap();
+//! let result = Simulation::new(&portfolio).trials(1000).run().unwrap();
+//! for trial in result.trials.iter().take(3) {
+//!     println!("{:.2}", trial.total() as f64 / 100.0);
+//! }
+//! ```
 
 pub mod allocation;
+pub mod costs;
+pub mod estimate;
+pub mod expertise;
+pub mod export;
+pub mod import;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod money;
+pub mod plan;
+pub mod portfolio;
 pub mod projects;
+pub mod recognition;
+pub mod simulation;
+pub mod stats;
+pub mod svg;
+pub mod terminal;
+pub mod timeline;
 pub mod traits;
 
 #[cfg(test)]
@@ -36,19 +76,22 @@ mod tests {
             .name("p1")
             .duration_weeks(3)
             .start_date(&(today + Duration::weeks(2)))
-            .build();
+            .build()
+            .unwrap();
 
         let p2 = ProjectBuilder::default()
             .name("p2")
             .duration_weeks(5)
             .start_date(&(today + Duration::weeks(8)))
-            .build();
+            .build()
+            .unwrap();
 
         let p3 = ProjectBuilder::default()
             .name("p3")
             .duration_weeks(5)
             .start_date(&(today + Duration::weeks(4)))
-            .build();
+            .build()
+            .unwrap();
 
         println!("{}", p1);
         println!("{}", p2);
@@ -1,29 +1,429 @@
-use chrono::{Duration, Utc};
-use hallo::projects::ProjectBuilder;
-
-pub fn main() {
-    let today = Utc::today();
-    let p1 = ProjectBuilder::default()
-        .name("p1")
-        .duration_weeks(3)
-        .start_date(&(today + Duration::weeks(2)))
-        .build();
-
-    let p2 = ProjectBuilder::default()
-        .name("p2")
-        .duration_weeks(5)
-        .value(5000)
-        .start_date(&(today + Duration::weeks(8)))
-        .build();
-
-    let p3 = ProjectBuilder::default()
-        .name("p3")
-        .duration_weeks(5)
-        .value(1000)
-        .start_date(&(today + Duration::weeks(4)))
-        .build();
-
-    println!("{}", p1);
-    println!("{}", p2);
-    println!("{}", p3);
+use chrono::{prelude::*, Duration};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::{bail, Result, WrapErr};
+use hallo::{
+    plan,
+    portfolio::Portfolio,
+    simulation::{Simulation, SimulationResult},
+    svg, terminal,
+    timeline::Granularity,
+};
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Outcome planning for upcoming projects using Monte Carlo simulations.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a simulation and summarise the outcomes.
+    Simulate {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Check a plan file for mistakes.
+    Validate {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+    },
+    /// List the projects and costs in a plan.
+    List {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Summarise the outcomes period by period.
+    Timeline {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        #[arg(long, value_enum, default_value_t = Period::Month)]
+        period: Period,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Write outcomes as CSV for spreadsheets.
+    Export {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        #[arg(long, value_enum, default_value_t = Period::Month)]
+        period: Period,
+        /// Write every trial instead of percentile bands.
+        #[arg(long)]
+        raw: bool,
+        /// File to write to, instead of the terminal.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Draw the running total over time and how the outcomes spread.
+    Chart {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        /// Columns to draw in. Defaults to the width of the terminal.
+        #[arg(long)]
+        width: Option<usize>,
+        /// Rows for the fan chart.
+        #[arg(long, default_value_t = 15)]
+        height: usize,
+        /// Bars in the histogram.
+        #[arg(long, default_value_t = 10)]
+        bins: usize,
+    },
+    /// Show how late projects start, once they slip and wait for the team.
+    Delays {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Show when projects happen and how they overlap.
+    Gantt {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        /// Day to mark as today, like 2022-09-01.
+        #[arg(long, value_parser = parse_date)]
+        today: Option<Date<Utc>>,
+        /// Columns to draw in. Defaults to the width of the terminal.
+        #[arg(long)]
+        width: Option<usize>,
+        /// Write SVG, or HTML when the file ends in .html, instead of drawing in the terminal.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write SVG charts for reports: fan.svg, gantt.svg and histogram.svg.
+    /// The Gantt chart marks --from as today.
+    Report {
+        /// Plan file (.toml, .yaml or .yml).
+        plan: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        /// Folder to write the charts to. Created when missing.
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+        /// Bars in the histogram.
+        #[arg(long, default_value_t = 20)]
+        bins: usize,
+    },
+}
+
+#[derive(Args)]
+struct SimulationArgs {
+    /// How many times to roll the dice.
+    #[arg(short, long, default_value_t = 1000)]
+    trials: usize,
+    /// Seed for reproducible results. Picked at random when left out.
+    #[arg(short, long)]
+    seed: Option<u64>,
+    /// First day to simulate, like 2022-09-01. Defaults to today.
+    #[arg(long, value_parser = parse_date)]
+    from: Option<Date<Utc>>,
+    /// Day the simulation stops, like 2023-09-01. Defaults to a year after --from.
+    #[arg(long, value_parser = parse_date)]
+    to: Option<Date<Utc>>,
+    /// Threads to run trials on. Defaults to one per CPU core.
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+impl SimulationArgs {
+    fn run(&self, portfolio: &Portfolio) -> Result<SimulationResult<Portfolio>> {
+        let from = self.from.unwrap_or_else(Utc::today);
+        let to = self.to.unwrap_or(from + Duration::weeks(52));
+        if to <= from {
+            bail!(
+                "--to ({}) must be after --from ({}).",
+                to.naive_utc(),
+                from.naive_utc()
+            );
+        }
+        let mut simulation = Simulation::new(portfolio)
+            .trials(self.trials)
+            .start_date(&from)
+            .end_date(&to);
+        if let Some(seed) = self.seed {
+            simulation = simulation.seed(seed);
+        }
+        if let Some(threads) = self.threads {
+            simulation = simulation.threads(threads);
+        }
+        Ok(simulation.run()?)
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Period {
+    Day,
+    Week,
+    Month,
+    Quarter,
+}
+
+impl From<Period> for Granularity {
+    fn from(period: Period) -> Self {
+        match period {
+            Period::Day => Granularity::Day,
+            Period::Week => Granularity::Week,
+            Period::Month => Granularity::Month,
+            Period::Quarter => Granularity::Quarter,
+        }
+    }
+}
+
+fn parse_date(text: &str) -> Result<Date<Utc>, String> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|date| Utc.from_utc_date(&date))
+        .map_err(|_| format!("\"{}\" isn't a date like 2022-09-01", text))
+}
+
+fn load(path: &Path) -> Result<Portfolio> {
+    plan::load(path).wrap_err_with(|| format!("Couldn't load {}", path.display()))
+}
+
+fn terminal_width() -> usize {
+    terminal_size::terminal_size().map_or(80, |(width, _)| width.0 as usize)
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    std::fs::write(path, contents).wrap_err_with(|| format!("Couldn't write {}", path.display()))
+}
+
+#[cfg(feature = "json")]
+fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
+    println!("{}", hallo::json::to_json_pretty(value)?);
+    Ok(())
+}
+
+#[cfg(not(feature = "json"))]
+fn print_json<T>(_: &T) -> Result<()> {
+    bail!("JSON output needs hallo built with `--features json`.")
+}
+
+pub fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    match Cli::parse().command {
+        Command::Simulate {
+            plan,
+            simulation,
+            format,
+        } => {
+            let portfolio = load(&plan)?;
+            let result = simulation.run(&portfolio)?;
+            match format {
+                Format::Text => {
+                    println!(
+                        "{} trials from {} to {} (seed {}), in {}",
+                        result.trials.len(),
+                        result.start_date.naive_utc(),
+                        result.end_date.naive_utc(),
+                        result.seed,
+                        portfolio.exchange_rates().reporting()
+                    );
+                    let summary = result.summary();
+                    println!("{}", summary);
+                    println!(
+                        "Chance of making money: {:.0}%",
+                        summary.probability_of_exceeding(0.0) * 100.0
+                    );
+                }
+                Format::Json => print_json(&result.summary())?,
+                Format::Csv => bail!("simulate can't write CSV. Try `hallo export`."),
+            }
+        }
+        Command::Validate { plan } => {
+            let portfolio = load(&plan)?;
+            println!(
+                "{} is valid: {} projects, {} costs.",
+                plan.display(),
+                portfolio.projects().len(),
+                portfolio.costs().len()
+            );
+        }
+        Command::List { plan, format } => {
+            let portfolio = load(&plan)?;
+            match format {
+                Format::Text => print!("{}", portfolio),
+                Format::Json => print_json(&portfolio)?,
+                Format::Csv => bail!("list can't write CSV."),
+            }
+        }
+        Command::Timeline {
+            plan,
+            simulation,
+            period,
+            format,
+        } => {
+            let result = simulation.run(&load(&plan)?)?;
+            let granularity = period.into();
+            match format {
+                Format::Text => {
+                    for (period, summary) in result.period_summaries(granularity) {
+                        let marker = if period.partial { "*" } else { " " };
+                        println!("{}{}  {}", period.start_date.naive_utc(), marker, summary);
+                    }
+                }
+                Format::Csv => result.write_bands_csv(granularity, std::io::stdout())?,
+                Format::Json => print_json(&result.period_summaries(granularity))?,
+            }
+        }
+        Command::Export {
+            plan,
+            simulation,
+            period,
+            raw,
+            output,
+        } => {
+            let result = simulation.run(&load(&plan)?)?;
+            let writer: Box<dyn Write> = match &output {
+                Some(path) => Box::new(
+                    File::create(path)
+                        .wrap_err_with(|| format!("Couldn't create {}", path.display()))?,
+                ),
+                None => Box::new(std::io::stdout()),
+            };
+            if raw {
+                result.write_trials_csv(period.into(), writer)?;
+            } else {
+                result.write_bands_csv(period.into(), writer)?;
+            }
+        }
+        Command::Chart {
+            plan,
+            simulation,
+            width,
+            height,
+            bins,
+        } => {
+            let portfolio = load(&plan)?;
+            let result = simulation.run(&portfolio)?;
+            let width = width.unwrap_or_else(terminal_width);
+            println!(
+                "Running total in {}, P10 to P90",
+                portfolio.exchange_rates().reporting()
+            );
+            println!("{}", terminal::fan_chart(&result.fan(), width, height));
+            println!("Outcomes at {}", result.end_date.naive_utc());
+            print!(
+                "{}",
+                terminal::histogram(&result.summary().histogram(bins), width)
+            );
+        }
+        Command::Delays {
+            plan,
+            simulation,
+            format,
+        } => {
+            let portfolio = load(&plan)?;
+            let result = simulation.run(&portfolio)?;
+            let delays = result.delays(&portfolio);
+            match format {
+                Format::Text => {
+                    let width = delays.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+                    for (name, days) in &delays {
+                        let happened = days.count() as f64 / result.trials.len().max(1) as f64;
+                        print!(
+                            "{:<w$}  happens {:>3.0}%",
+                            name,
+                            happened * 100.0,
+                            w = width
+                        );
+                        if days.count() > 0 {
+                            print!(
+                                "  days late: P10 {:.0} | P50 {:.0} | P90 {:.0} | max {}",
+                                days.p10(),
+                                days.p50(),
+                                days.p90(),
+                                days.max()
+                            );
+                        }
+                        println!();
+                    }
+                }
+                Format::Json => print_json(&delays)?,
+                Format::Csv => bail!("delays can't write CSV."),
+            }
+        }
+        Command::Gantt {
+            plan,
+            today,
+            width,
+            output,
+        } => {
+            let portfolio = load(&plan)?;
+            let today = today.unwrap_or_else(Utc::today);
+            match output {
+                Some(path) => {
+                    let chart = svg::gantt(portfolio.projects(), &today, 800);
+                    if path.extension().is_some_and(|e| e == "html") {
+                        write(&path, &svg::html(&plan.display().to_string(), &[chart]))?;
+                    } else {
+                        write(&path, &chart)?;
+                    }
+                }
+                None => print!(
+                    "{}",
+                    terminal::gantt(
+                        portfolio.projects(),
+                        &today,
+                        width.unwrap_or_else(terminal_width)
+                    )
+                ),
+            }
+        }
+        Command::Report {
+            plan,
+            simulation,
+            output,
+            bins,
+        } => {
+            let portfolio = load(&plan)?;
+            let result = simulation.run(&portfolio)?;
+            std::fs::create_dir_all(&output)
+                .wrap_err_with(|| format!("Couldn't create {}", output.display()))?;
+            write(
+                &output.join("fan.svg"),
+                &svg::fan_chart(&result.fan(), 800, 400),
+            )?;
+            write(
+                &output.join("gantt.svg"),
+                &svg::gantt(portfolio.projects(), &result.start_date, 800),
+            )?;
+            write(
+                &output.join("histogram.svg"),
+                &svg::histogram(&result.summary().histogram(bins), 800, 400),
+            )?;
+            println!(
+                "Wrote fan.svg, gantt.svg and histogram.svg to {}",
+                output.display()
+            );
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,371 @@
+//! Amounts of money, the currency they're in, and the rates
+//! for converting them to the currency we report in.
+
+use std::collections::BTreeMap;
+
+#[derive(PartialEq, Debug)]
+pub enum MoneyError {
+    /// Amounts in different currencies can't be added without converting them first.
+    CurrencyMismatch(Currency, Currency),
+    InvalidCurrency(String),
+    InvalidRate(Currency),
+    Overflow,
+    UnknownRate(Currency),
+}
+
+impl std::error::Error for MoneyError {}
+impl std::fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            MoneyError::CurrencyMismatch(left, right) => {
+                write!(f, "Can't add {} to {} without converting.", right, left)
+            }
+            MoneyError::InvalidCurrency(code) => write!(
+                f,
+                "\"{}\" isn't a currency code. Use three capital letters, like GBP.",
+                code
+            ),
+            MoneyError::InvalidRate(currency) => write!(
+                f,
+                "Exchange rate for {} must be a positive number.",
+                currency
+            ),
+            MoneyError::Overflow => write!(f, "Amount is too large."),
+            MoneyError::UnknownRate(currency) => {
+                write!(f, "There's no exchange rate for {}.", currency)
+            }
+        }
+    }
+}
+
+/// # Currency
+/// A three letter ISO 4217 currency code, like `GBP` or `EUR`.
+/// Amounts are in GBP unless told otherwise.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(try_from = "String", into = "String"))]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const GBP: Currency = Currency(*b"GBP");
+    pub const EUR: Currency = Currency(*b"EUR");
+    pub const USD: Currency = Currency(*b"USD");
+
+    /// Reads a currency code.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, MoneyError};
+    ///
+    /// assert_eq!(Currency::new("EUR"), Ok(Currency::EUR));
+    /// assert_eq!(Currency::new("euro"), Err(MoneyError::InvalidCurrency("euro".into())))
+    /// ```
+    pub fn new(code: &str) -> Result<Currency, MoneyError> {
+        match code.as_bytes() {
+            [a, b, c] if code.bytes().all(|b| b.is_ascii_uppercase()) => Ok(Currency([*a, *b, *c])),
+            _ => Err(MoneyError::InvalidCurrency(code.into())),
+        }
+    }
+
+    /// Returns the currency code.
+    pub fn code(&self) -> &str {
+        // Only ever built from ASCII letters.
+        std::str::from_utf8(&self.0).unwrap_or("???")
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::GBP
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl std::str::FromStr for Currency {
+    type Err = MoneyError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Currency::new(code)
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = MoneyError;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Currency::new(&code)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.code().into()
+    }
+}
+
+/// # Money
+/// An amount in a given currency, kept to the nearest hundredth
+/// of a unit (pence, cents) so it adds up exactly.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Money {
+    currency: Currency,
+    minor: i64,
+}
+
+impl Money {
+    /// Creates an amount of whole units, and fractions of them,
+    /// rounded to the nearest hundredth.
+    /// Amounts too large to keep saturate; use [`Money::try_new`] to catch them.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, Money};
+    ///
+    /// let money = Money::new(12.345, Currency::EUR);
+    /// assert_eq!(money.minor(), 1235);
+    /// assert_eq!(money.to_string(), "12.35 EUR")
+    /// ```
+    pub fn new(amount: f64, currency: Currency) -> Money {
+        Money {
+            currency,
+            minor: (amount * 100.0).round() as i64,
+        }
+    }
+
+    /// Creates an amount of whole units, and fractions of them,
+    /// rounded to the nearest hundredth, if it's small enough to keep.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, Money, MoneyError};
+    ///
+    /// assert_eq!(Money::try_new(12.5, Currency::GBP), Ok(Money::new(12.5, Currency::GBP)));
+    /// assert_eq!(Money::try_new(1e20, Currency::GBP), Err(MoneyError::Overflow));
+    /// assert_eq!(Money::try_new(f64::NAN, Currency::GBP), Err(MoneyError::Overflow))
+    /// ```
+    pub fn try_new(amount: f64, currency: Currency) -> Result<Money, MoneyError> {
+        let minor = (amount * 100.0).round();
+        match minor.abs() < i64::MAX as f64 {
+            true => Ok(Money::from_minor(minor as i64, currency)),
+            false => Err(MoneyError::Overflow),
+        }
+    }
+
+    /// Creates an amount in hundredths of a unit.
+    pub fn from_minor(minor: i64, currency: Currency) -> Money {
+        Money { currency, minor }
+    }
+
+    /// Returns the currency the amount is in.
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Returns the amount in hundredths of a unit.
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    /// Returns the amount in units.
+    pub fn amount(&self) -> f64 {
+        self.minor as f64 / 100.0
+    }
+
+    /// Returns the amount rounded to whole units.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, Money};
+    ///
+    /// assert_eq!(Money::from_minor(-250, Currency::GBP).units(), -3);
+    /// assert_eq!(Money::from_minor(149, Currency::GBP).units(), 1)
+    /// ```
+    pub fn units(&self) -> i64 {
+        let units = self.minor / 100;
+        match self.minor % 100 {
+            rest if rest >= 50 => units + 1,
+            rest if rest <= -50 => units - 1,
+            _ => units,
+        }
+    }
+
+    /// Adds two amounts in the same currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, Money, MoneyError};
+    ///
+    /// let pounds = Money::new(10.0, Currency::GBP);
+    /// let euros = Money::new(10.0, Currency::EUR);
+    /// assert_eq!(pounds.checked_add(&pounds), Ok(Money::new(20.0, Currency::GBP)));
+    /// assert_eq!(
+    ///     pounds.checked_add(&euros),
+    ///     Err(MoneyError::CurrencyMismatch(Currency::GBP, Currency::EUR))
+    /// )
+    /// ```
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+        match self.minor.checked_add(other.minor) {
+            Some(minor) => Ok(Money::from_minor(minor, self.currency)),
+            None => Err(MoneyError::Overflow),
+        }
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let minor = self.minor.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:02} {}",
+            sign,
+            minor / 100,
+            minor % 100,
+            self.currency
+        )
+    }
+}
+
+/// # ExchangeRates
+/// How much one unit of every other currency is worth
+/// in the currency we report in.
+#[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(try_from = "ExchangeRatesInput"))]
+pub struct ExchangeRates {
+    rates: BTreeMap<Currency, f64>,
+    reporting: Currency,
+}
+
+/// Rates as written in JSON, before they're checked.
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ExchangeRatesInput {
+    rates: BTreeMap<Currency, f64>,
+    reporting: Currency,
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<ExchangeRatesInput> for ExchangeRates {
+    type Error = MoneyError;
+
+    fn try_from(input: ExchangeRatesInput) -> Result<Self, Self::Error> {
+        let mut rates = ExchangeRates::new(input.reporting);
+        for (currency, rate) in input.rates {
+            rates.add_rate(currency, rate)?;
+        }
+        Ok(rates)
+    }
+}
+
+impl ExchangeRates {
+    /// Creates a table for reporting in `reporting`, with no other rates yet.
+    pub fn new(reporting: Currency) -> ExchangeRates {
+        ExchangeRates {
+            rates: BTreeMap::new(),
+            reporting,
+        }
+    }
+
+    /// Returns the currency we report in.
+    pub fn reporting(&self) -> Currency {
+        self.reporting
+    }
+
+    /// Sets how much one unit of `currency` is worth in the reporting currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, ExchangeRates, MoneyError};
+    ///
+    /// let mut rates = ExchangeRates::new(Currency::GBP);
+    /// assert!(rates.add_rate(Currency::EUR, 0.85).is_ok());
+    /// assert_eq!(rates.add_rate(Currency::USD, 0.0), Err(MoneyError::InvalidRate(Currency::USD)))
+    /// ```
+    pub fn add_rate(&mut self, currency: Currency, rate: f64) -> Result<(), MoneyError> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(MoneyError::InvalidRate(currency));
+        }
+        self.rates.insert(currency, rate);
+        Ok(())
+    }
+
+    /// Returns how much one unit of `currency` is worth in the reporting currency.
+    pub fn rate(&self, currency: Currency) -> Result<f64, MoneyError> {
+        if currency == self.reporting {
+            return Ok(1.0);
+        }
+        self.rates
+            .get(&currency)
+            .copied()
+            .ok_or(MoneyError::UnknownRate(currency))
+    }
+
+    /// Converts an amount to the reporting currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, ExchangeRates, Money};
+    ///
+    /// let mut rates = ExchangeRates::new(Currency::GBP);
+    /// rates.add_rate(Currency::EUR, 0.85).unwrap();
+    /// assert_eq!(
+    ///     rates.convert(&Money::new(100.0, Currency::EUR)),
+    ///     Ok(Money::new(85.0, Currency::GBP))
+    /// )
+    /// ```
+    pub fn convert(&self, money: &Money) -> Result<Money, MoneyError> {
+        let minor = (money.minor as f64 * self.rate(money.currency)?).round();
+        if minor.abs() >= i64::MAX as f64 {
+            return Err(MoneyError::Overflow);
+        }
+        Ok(Money::from_minor(minor as i64, self.reporting))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn large_amounts_fit() {
+        let billions = Money::new(5e9, Currency::GBP);
+        assert_eq!(billions.units(), 5_000_000_000);
+        assert_eq!(
+            billions.checked_add(&billions).map(|m| m.units()),
+            Ok(10_000_000_000)
+        );
+        let most = Money::from_minor(i64::MAX, Currency::GBP);
+        assert_eq!(most.checked_add(&most), Err(MoneyError::Overflow))
+    }
+
+    #[test]
+    fn reporting_currency_needs_no_rate() {
+        let rates = ExchangeRates::new(Currency::EUR);
+        let money = Money::new(12.5, Currency::EUR);
+        assert_eq!(rates.convert(&money), Ok(money));
+        assert_eq!(
+            rates.convert(&Money::new(1.0, Currency::USD)),
+            Err(MoneyError::UnknownRate(Currency::USD))
+        )
+    }
+
+    #[test]
+    fn negative_amounts_display() {
+        assert_eq!(
+            Money::from_minor(-5, Currency::USD).to_string(),
+            "-0.05 USD"
+        );
+        assert_eq!(Money::from_minor(-1205, Currency::USD).units(), -12)
+    }
+}
@@ -0,0 +1,986 @@
+use crate::{
+    costs::{CostBuilder, CostKind, Recurrence},
+    estimate::Estimate,
+    expertise::{Expert, Team},
+    money::{Currency, ExchangeRates},
+    portfolio::{Portfolio, PortfolioError},
+    projects::{largest, Predecessor, ProjectBuilder, MAX_DAYS},
+    recognition::{Milestone, Recognition},
+};
+use chrono::{prelude::*, Duration};
+use serde::{
+    de::{self, Error as _},
+    Deserialize, Deserializer,
+};
+use std::{collections::BTreeMap, path::Path};
+
+#[derive(PartialEq, Debug)]
+pub enum PlanError {
+    /// A field is missing or wrong. `line` is 1-based, when known.
+    Invalid {
+        line: Option<usize>,
+        message: String,
+    },
+    Unreadable(String),
+    UnknownFormat(String),
+}
+
+impl std::error::Error for PlanError {}
+impl std::fmt::Display for PlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            PlanError::Invalid {
+                line: Some(line),
+                message,
+            } => write!(f, "line {}: {}", line, message),
+            PlanError::Invalid {
+                line: None,
+                message,
+            } => write!(f, "{}", message),
+            PlanError::Unreadable(reason) => write!(f, "Plan can't be read: {}", reason),
+            PlanError::UnknownFormat(path) => {
+                write!(f, "\"{}\" isn't a .toml, .yaml or .yml plan.", path)
+            }
+        }
+    }
+}
+
+/// # Format
+/// The languages a plan can be written in.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Format {
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    /// Picks a format from a file's extension.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::plan::Format;
+    ///
+    /// assert_eq!(Format::from_path("plan.yml"), Some(Format::Yaml));
+    /// assert_eq!(Format::from_path("plan.txt"), None)
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Format> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a plan file into a Portfolio.
+/// The format is picked from the file's extension.
+pub fn load(path: impl AsRef<Path>) -> Result<Portfolio, PlanError> {
+    let path = path.as_ref();
+    let format = Format::from_path(path)
+        .ok_or_else(|| PlanError::UnknownFormat(path.display().to_string()))?;
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| PlanError::Unreadable(format!("{}: {}", path.display(), e)))?;
+    parse(&source, format)
+}
+
+/// Turns a plan into a Portfolio.
+///
+/// Projects, costs and the people on the team are listed
+/// under `projects`, `costs` and `team`.
+/// Anything left out falls back to the builders' defaults.
+///
+/// Amounts are in `currency` (GBP unless set) unless a project
+/// or cost has its own. `exchange_rates` says how much one unit
+/// of every other currency is worth in `currency`.
+///
+/// ## Example
+/// ```
+/// use hallo::plan::{parse, Format, PlanError};
+///
+/// let plan = r#"
+/// [[projects]]
+/// name = "Website"
+/// value = { distribution = "pert", min = 8000, likely = 10000, max = 15000 }
+/// start = 2022-09-01
+/// duration_weeks = 6
+/// probability = 0.8
+///
+/// [[costs]]
+/// name = "Hosting"
+/// amount = 50
+/// kind = "licence"
+/// start = "2022-09-01"
+/// every = "monthly"
+/// "#;
+/// let portfolio = parse(plan, Format::Toml).unwrap();
+/// assert_eq!(portfolio.project("Website").unwrap().value().units(), 10500);
+///
+/// let typo = "projects:\n  - name: Website\n    probability: 80\n";
+/// let error = parse(typo, Format::Yaml).unwrap_err();
+/// assert!(matches!(error, PlanError::Invalid { line: Some(3), .. }))
+/// ```
+pub fn parse(source: &str, format: Format) -> Result<Portfolio, PlanError> {
+    let file: PlanFile = match format {
+        Format::Toml => toml::from_str(source).map_err(|e| PlanError::Invalid {
+            line: e.span().map(|span| line_at(source, span.start)),
+            message: e.message().to_string(),
+        })?,
+        Format::Yaml => serde_yaml::from_str(source).map_err(|e| {
+            let line = e.location().map(|location| location.line());
+            // Keep the path into the plan, drop the position we report ourselves.
+            let message = e.to_string();
+            let message = match message.rfind(" at line ") {
+                Some(index) => message[..index].to_string(),
+                None => message,
+            };
+            PlanError::Invalid { line, message }
+        })?,
+    };
+    file.into_portfolio(source, format)
+}
+
+/// Returns the 1-based line a byte offset falls on.
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// A step on the way to a value in a plan:
+/// a key in a table, or a position in a list.
+#[derive(Clone, Copy)]
+enum Step<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Returns the 1-based line the value at `path` starts on, if it's there.
+///
+/// Reads the plan again and fails on purpose once it gets to the value,
+/// so the line comes from the parser rather than from searching the text.
+fn line_of(source: &str, format: Format, path: &[Step]) -> Option<usize> {
+    let seed = Locate(path);
+    match format {
+        Format::Toml => {
+            let error =
+                de::DeserializeSeed::deserialize(seed, toml::Deserializer::new(source)).err()?;
+            error.span().map(|span| line_at(source, span.start))
+        }
+        Format::Yaml => {
+            let deserializer = serde_yaml::Deserializer::from_str(source);
+            let error = de::DeserializeSeed::deserialize(seed, deserializer).err()?;
+            error.location().map(|location| location.line())
+        }
+    }
+}
+
+/// Walks down a path, skipping everything else, and fails where it ends.
+struct Locate<'p>(&'p [Step<'p>]);
+
+impl<'de> de::DeserializeSeed<'de> for Locate<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+// Anything but a table or list is the end of the path,
+// and the default for those is to fail.
+impl<'de> de::Visitor<'de> for Locate<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the end of the path")
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let (key, rest) = match self.0.split_first() {
+            Some((Step::Key(key), rest)) => (*key, rest),
+            _ => return Err(A::Error::custom("found it")),
+        };
+        while let Some(next) = map.next_key::<String>()? {
+            if next == key {
+                return map.next_value_seed(Locate(rest));
+            }
+            map.next_value::<de::IgnoredAny>()?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let (index, rest) = match self.0.split_first() {
+            Some((Step::Index(index), rest)) => (*index, rest),
+            _ => return Err(A::Error::custom("found it")),
+        };
+        for _ in 0..index {
+            if seq.next_element::<de::IgnoredAny>()?.is_none() {
+                return Ok(());
+            }
+        }
+        seq.next_element_seed(Locate(rest)).map(|_| ())
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct PlanFile {
+    costs: Vec<CostPlan>,
+    #[serde(deserialize_with = "currency")]
+    currency: Option<Currency>,
+    exchange_rates: BTreeMap<String, f64>,
+    projects: Vec<ProjectPlan>,
+    team: Vec<ExpertPlan>,
+}
+
+impl PlanFile {
+    fn into_portfolio(self, source: &str, format: Format) -> Result<Portfolio, PlanError> {
+        // Points at the `index`th entry under `section`, named `name`.
+        let invalid =
+            |section: &str, index: usize, name: &str, message: String| PlanError::Invalid {
+                line: line_of(source, format, &[Step::Key(section), Step::Index(index)]),
+                message: format!("\"{}\": {}", name, message),
+            };
+        let mut portfolio = Portfolio::default();
+        let currency = self.currency.unwrap_or_default();
+        let mut rates = ExchangeRates::new(currency);
+        for (code, rate) in self.exchange_rates {
+            Currency::new(&code)
+                .and_then(|currency| rates.add_rate(currency, rate))
+                .map_err(|e| PlanError::Invalid {
+                    line: line_of(
+                        source,
+                        format,
+                        &[Step::Key("exchange_rates"), Step::Key(&code)],
+                    ),
+                    message: e.to_string(),
+                })?;
+        }
+        portfolio
+            .set_exchange_rates(rates)
+            .map_err(|e| PlanError::Invalid {
+                line: None,
+                message: e.to_string(),
+            })?;
+        for (index, mut plan) in self.projects.into_iter().enumerate() {
+            let name = plan.name.clone();
+            let invalid = |message| invalid("projects", index, &name, message);
+            plan.currency.get_or_insert(currency);
+            let project = plan
+                .into_builder()
+                .and_then(|builder| builder.build().map_err(|e| e.to_string()))
+                .map_err(invalid)?;
+            portfolio
+                .add_project(project)
+                .map_err(|e| invalid(e.to_string()))?;
+        }
+        for (index, mut plan) in self.costs.into_iter().enumerate() {
+            let name = plan.name.clone();
+            let invalid = |message| invalid("costs", index, &name, message);
+            plan.currency.get_or_insert(currency);
+            let cost = plan
+                .into_builder()
+                .build()
+                .map_err(|e| invalid(e.to_string()))?;
+            portfolio
+                .add_cost(cost)
+                .map_err(|e| invalid(e.to_string()))?;
+        }
+        portfolio.set_team(Team {
+            members: self.team.into_iter().map(ExpertPlan::into_expert).collect(),
+        });
+        let project = |name: &str, e: &PortfolioError| {
+            let index = portfolio.projects().iter().position(|p| p.name == name);
+            invalid("projects", index.unwrap_or(0), name, e.to_string())
+        };
+        portfolio.check_relationships().map_err(|e| match &e {
+            PortfolioError::Cycle(names) => project(&names[0], &e),
+            PortfolioError::UnknownReference { from, .. } => project(from, &e),
+            _ => PlanError::Invalid {
+                line: None,
+                message: e.to_string(),
+            },
+        })?;
+        Ok(portfolio)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectPlan {
+    correlation: Option<CorrelationPlan>,
+    #[serde(default, deserialize_with = "currency")]
+    currency: Option<Currency>,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    #[serde(default, deserialize_with = "duration_days")]
+    duration_days: Option<Estimate>,
+    #[serde(default, deserialize_with = "duration_weeks")]
+    duration_weeks: Option<u32>,
+    #[serde(default)]
+    excludes: Vec<String>,
+    #[serde(default, deserialize_with = "milestones")]
+    milestones: Option<Recognition>,
+    name: String,
+    one_of: Option<String>,
+    #[serde(default, deserialize_with = "probability")]
+    probability: Option<f64>,
+    recognition: Option<RecognitionPlan>,
+    #[serde(default)]
+    requires: Vec<RequirementPlan>,
+    #[serde(default, deserialize_with = "date")]
+    start: Option<Date<Utc>>,
+    #[serde(default, deserialize_with = "start_delay")]
+    start_delay: Option<Estimate>,
+    #[serde(default)]
+    starts_after: Vec<PredecessorPlan>,
+    #[serde(default)]
+    starts_when_team_free: bool,
+    #[serde(default, deserialize_with = "non_negative_estimate")]
+    value: Option<Estimate>,
+}
+
+impl ProjectPlan {
+    fn into_builder(self) -> Result<ProjectBuilder, String> {
+        let mut builder = ProjectBuilder::default().name(&self.name);
+        if let Some(start) = self.start {
+            builder = builder.start_date(&start);
+        }
+        builder = match (self.duration_days, self.duration_weeks) {
+            (Some(_), Some(_)) => {
+                return Err("set duration_days or duration_weeks, not both.".into());
+            }
+            (Some(days), None) => builder.duration_estimate(days),
+            (None, Some(weeks)) => builder.duration_weeks(i64::from(weeks)),
+            (None, None) => builder,
+        };
+        if let Some(value) = self.value {
+            builder = builder.value_estimate(value);
+        }
+        if let Some(currency) = self.currency {
+            builder = builder.currency(currency);
+        }
+        if let Some(probability) = self.probability {
+            builder = builder.probability(probability);
+        }
+        if let Some(start_delay) = self.start_delay {
+            builder = builder.start_delay(start_delay);
+        }
+        builder = match (self.recognition, self.milestones) {
+            (Some(_), Some(_)) => {
+                return Err("set recognition or milestones, not both.".into());
+            }
+            (Some(recognition), None) => builder.recognition(recognition.into()),
+            (None, Some(milestones)) => builder.recognition(milestones),
+            (None, None) => builder,
+        };
+        for requirement in self.requires {
+            builder = builder.requires(&requirement.skill, requirement.fte);
+        }
+        for project in self.depends_on {
+            builder = builder.depends_on(&project);
+        }
+        for project in self.excludes {
+            builder = builder.excludes(&project);
+        }
+        if let Some(group) = self.one_of {
+            builder = builder.one_of(&group);
+        }
+        for predecessor in self.starts_after {
+            builder =
+                builder.starts_after(&predecessor.project, Duration::days(predecessor.lag_days));
+        }
+        if self.starts_when_team_free {
+            builder = builder.starts_when_team_free();
+        }
+        if let Some(correlation) = self.correlation {
+            builder = builder.correlated(&correlation.group, correlation.strength);
+        }
+        Ok(builder)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PredecessorPlan {
+    #[serde(default, deserialize_with = "lag_days")]
+    lag_days: i64,
+    project: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CorrelationPlan {
+    group: String,
+    strength: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecognitionPlan {
+    Daily,
+    Start,
+    End,
+    Monthly,
+}
+
+impl From<RecognitionPlan> for Recognition {
+    fn from(plan: RecognitionPlan) -> Self {
+        match plan {
+            RecognitionPlan::Daily => Recognition::Daily,
+            RecognitionPlan::Start => Recognition::Start,
+            RecognitionPlan::End => Recognition::End,
+            RecognitionPlan::Monthly => Recognition::Monthly,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MilestonePlan {
+    progress: f64,
+    share: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RequirementPlan {
+    #[serde(deserialize_with = "fte")]
+    fte: f64,
+    skill: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpertPlan {
+    #[serde(deserialize_with = "fte")]
+    capacity: f64,
+    name: String,
+    skills: Vec<String>,
+}
+
+impl ExpertPlan {
+    fn into_expert(self) -> Expert {
+        Expert {
+            capacity: self.capacity,
+            name: self.name,
+            skills: self.skills,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CostPlan {
+    amount: Option<u64>,
+    #[serde(default, deserialize_with = "currency")]
+    currency: Option<Currency>,
+    #[serde(default, deserialize_with = "date")]
+    end: Option<Date<Utc>>,
+    every: Option<RecurrencePlan>,
+    kind: Option<CostKindPlan>,
+    name: String,
+    #[serde(default, deserialize_with = "date")]
+    start: Option<Date<Utc>>,
+}
+
+impl CostPlan {
+    fn into_builder(self) -> CostBuilder {
+        let mut builder = CostBuilder::default().name(&self.name);
+        if let Some(amount) = self.amount {
+            builder = builder.amount(amount);
+        }
+        if let Some(currency) = self.currency {
+            builder = builder.currency(currency);
+        }
+        if let Some(kind) = self.kind {
+            builder = builder.kind(kind.into());
+        }
+        if let Some(start) = self.start {
+            builder = builder.start_date(&start);
+        }
+        if let Some(end) = self.end {
+            builder = builder.end_date(&end);
+        }
+        if let Some(every) = self.every {
+            builder = builder.every(every.into());
+        }
+        builder
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CostKindPlan {
+    ContractorFee,
+    Licence,
+    Other,
+    Salary,
+}
+
+impl From<CostKindPlan> for CostKind {
+    fn from(plan: CostKindPlan) -> Self {
+        match plan {
+            CostKindPlan::ContractorFee => CostKind::ContractorFee,
+            CostKindPlan::Licence => CostKind::Licence,
+            CostKindPlan::Other => CostKind::Other,
+            CostKindPlan::Salary => CostKind::Salary,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecurrencePlan {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl From<RecurrencePlan> for Recurrence {
+    fn from(plan: RecurrencePlan) -> Self {
+        match plan {
+            RecurrencePlan::Weekly => Recurrence::Weekly,
+            RecurrencePlan::Monthly => Recurrence::Monthly,
+            RecurrencePlan::Yearly => Recurrence::Yearly,
+        }
+    }
+}
+
+/// A range of possibilities, written as a table naming its distribution.
+#[derive(Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case", deny_unknown_fields)]
+enum DistributionPlan {
+    Uniform { min: f64, max: f64 },
+    Triangular { min: f64, likely: f64, max: f64 },
+    Pert { min: f64, likely: f64, max: f64 },
+    LogNormal { mean: f64, std_dev: f64 },
+}
+
+impl From<DistributionPlan> for Estimate {
+    fn from(plan: DistributionPlan) -> Self {
+        match plan {
+            DistributionPlan::Uniform { min, max } => Estimate::Uniform { min, max },
+            DistributionPlan::Triangular { min, likely, max } => {
+                Estimate::Triangular { min, likely, max }
+            }
+            DistributionPlan::Pert { min, likely, max } => Estimate::Pert { min, likely, max },
+            DistributionPlan::LogNormal { mean, std_dev } => Estimate::LogNormal { mean, std_dev },
+        }
+    }
+}
+
+// Field-level checks run inside the visitors, while the parser still
+// knows where the value is, so errors point at the right line.
+
+/// Reads an Estimate: either a plain number or a distribution.
+/// Estimates of days name their field, and are kept within a century.
+struct EstimateVisitor {
+    days: Option<&'static str>,
+    non_negative: bool,
+}
+
+impl EstimateVisitor {
+    fn check<E: de::Error>(&self, estimate: Estimate) -> Result<Estimate, E> {
+        if !estimate.is_valid() {
+            return Err(E::custom(format!("{} isn't a valid estimate.", estimate)));
+        }
+        if let Some(field) = self.days {
+            if estimate.min() < 0.0 || largest(&estimate) > MAX_DAYS as f64 {
+                return Err(E::custom(format!(
+                    "{} {} must be between 0 and {}.",
+                    field, estimate, MAX_DAYS
+                )));
+            }
+        }
+        if self.non_negative && estimate.min() < 0.0 {
+            return Err(E::custom(format!("{} can't be negative.", estimate)));
+        }
+        Ok(estimate)
+    }
+}
+
+impl<'de> de::Visitor<'de> for EstimateVisitor {
+    type Value = Estimate;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a number or a table with a distribution")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Estimate, E> {
+        self.check(Estimate::Fixed(value as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Estimate, E> {
+        self.check(Estimate::Fixed(value as f64))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Estimate, E> {
+        self.check(Estimate::Fixed(value))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Estimate, A::Error> {
+        let estimate = DistributionPlan::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        self.check(estimate.into())
+    }
+}
+
+fn non_negative_estimate<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Estimate>, D::Error> {
+    deserializer
+        .deserialize_any(EstimateVisitor {
+            days: None,
+            non_negative: true,
+        })
+        .map(Some)
+}
+
+fn duration_days<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Estimate>, D::Error> {
+    deserializer
+        .deserialize_any(EstimateVisitor {
+            days: Some("duration_days"),
+            non_negative: true,
+        })
+        .map(Some)
+}
+
+fn start_delay<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Estimate>, D::Error> {
+    deserializer
+        .deserialize_any(EstimateVisitor {
+            days: Some("start_delay"),
+            non_negative: true,
+        })
+        .map(Some)
+}
+
+/// Reads a number and checks it.
+struct NumberVisitor(fn(f64) -> Result<f64, String>);
+
+impl<'de> de::Visitor<'de> for NumberVisitor {
+    type Value = f64;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a number")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<f64, E> {
+        self.visit_f64(value as f64)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<f64, E> {
+        self.visit_f64(value as f64)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<f64, E> {
+        (self.0)(value).map_err(E::custom)
+    }
+}
+
+fn probability<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    deserializer
+        .deserialize_any(NumberVisitor(|probability| {
+            match (0.0..=1.0).contains(&probability) {
+                true => Ok(probability),
+                false => Err(format!(
+                    "probability {} must be between 0.0 and 1.0.",
+                    probability
+                )),
+            }
+        }))
+        .map(Some)
+}
+
+fn fte<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    deserializer.deserialize_any(NumberVisitor(|fte| match fte.is_finite() && fte > 0.0 {
+        true => Ok(fte),
+        false => Err(format!("fte {} must be more than 0.", fte)),
+    }))
+}
+
+fn lag_days<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = i64;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a whole number of days")
+        }
+
+        fn visit_i64<E: de::Error>(self, days: i64) -> Result<i64, E> {
+            match days.abs() <= Predecessor::MAX_LAG_DAYS {
+                true => Ok(days),
+                false => Err(E::custom(format!(
+                    "lag_days {} must be between -{max} and {max}.",
+                    days,
+                    max = Predecessor::MAX_LAG_DAYS
+                ))),
+            }
+        }
+
+        fn visit_u64<E: de::Error>(self, days: u64) -> Result<i64, E> {
+            self.visit_i64(i64::try_from(days).unwrap_or(i64::MAX))
+        }
+    }
+
+    deserializer.deserialize_i64(Visitor)
+}
+
+fn duration_weeks<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    struct Visitor;
+
+    const MAX_WEEKS: i64 = MAX_DAYS / 7;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = u32;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a whole number of weeks")
+        }
+
+        fn visit_i64<E: de::Error>(self, weeks: i64) -> Result<u32, E> {
+            match (0..=MAX_WEEKS).contains(&weeks) {
+                true => Ok(weeks as u32),
+                false => Err(E::custom(format!(
+                    "duration_weeks {} must be between 0 and {}.",
+                    weeks, MAX_WEEKS
+                ))),
+            }
+        }
+
+        fn visit_u64<E: de::Error>(self, weeks: u64) -> Result<u32, E> {
+            self.visit_i64(i64::try_from(weeks).unwrap_or(i64::MAX))
+        }
+    }
+
+    deserializer.deserialize_i64(Visitor).map(Some)
+}
+
+fn currency<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Currency>, D::Error> {
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Currency;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a currency code like GBP")
+        }
+
+        fn visit_str<E: de::Error>(self, code: &str) -> Result<Currency, E> {
+            Currency::new(code).map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_str(Visitor).map(Some)
+}
+
+fn milestones<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Recognition>, D::Error> {
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Recognition;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a list of milestones")
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Recognition, A::Error> {
+            let mut milestones = vec![];
+            while let Some(m) = seq.next_element::<MilestonePlan>()? {
+                milestones.push(Milestone {
+                    progress: m.progress,
+                    share: m.share,
+                });
+            }
+            let recognition = Recognition::Milestones(milestones);
+            if !recognition.is_valid() {
+                return Err(A::Error::custom(
+                    "milestones need progress from 0.0 to 1.0 and shares adding up to 1.0.",
+                ));
+            }
+            Ok(recognition)
+        }
+    }
+
+    deserializer.deserialize_seq(Visitor).map(Some)
+}
+
+/// Reads a date written as `2022-09-01`, quoted or (in TOML) not.
+fn date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Date<Utc>>, D::Error> {
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Date<Utc>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a date like 2022-09-01")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Date<Utc>, E> {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| E::custom(format!("\"{}\" isn't a date like 2022-09-01.", value)))?;
+            Ok(Utc.from_utc_date(&date))
+        }
+
+        // TOML hands over bare dates as a single-entry table.
+        fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Date<Utc>, A::Error> {
+            match map.next_entry::<String, String>()? {
+                Some((_, value)) => self.visit_str(&value),
+                None => Err(A::Error::custom("expected a date like 2022-09-01")),
+            }
+        }
+    }
+
+    deserializer.deserialize_any(Visitor).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn example_plans_agree() {
+        let toml = parse(include_str!("../examples/plan.toml"), Format::Toml).unwrap();
+        let yaml = parse(include_str!("../examples/plan.yaml"), Format::Yaml).unwrap();
+        assert_eq!(toml, yaml);
+        assert_eq!(toml.projects().len(), 3);
+        assert_eq!(
+            toml.project("Mobile app").unwrap().dependencies(),
+            ["Website"]
+        );
+        assert_eq!(toml.costs().len(), 2);
+        assert_eq!(toml.team().capacity("design"), 1.0);
+        assert_eq!(toml.project("Workshop").unwrap().currency(), Currency::EUR);
+        assert_eq!(toml.exchange_rates().rate(Currency::EUR), Ok(0.85))
+    }
+
+    #[test]
+    fn currencies_need_exchange_rates() {
+        let plan = "currency = \"EUR\"\n\n[[projects]]\nname = \"a\"\n\n[[projects]]\nname = \"b\"\ncurrency = \"USD\"\n";
+        let error = parse(plan, Format::Toml).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 6: \"b\": \"b\" is in USD, but there's no exchange rate for it."
+        );
+
+        let plan = "currency: EUR\nexchange_rates:\n  GBP: 1.15\n  usd: 0.8\n";
+        let error = parse(plan, Format::Yaml).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4: \"usd\" isn't a currency code. Use three capital letters, like GBP."
+        );
+
+        let plan = "currency = \"EUR\"\n\n[exchange_rates]\nGBP = 1.15\nUSD = -0.9\n";
+        let error = parse(plan, Format::Toml).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 5: Exchange rate for USD must be a positive number."
+        )
+    }
+
+    #[test]
+    fn invalid_fields_report_their_line() {
+        let plan = "[[projects]]\nname = \"a\"\n\n[[projects]]\nname = \"b\"\nprobability = 1.5\n";
+        let error = parse(plan, Format::Toml).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 6: probability 1.5 must be between 0.0 and 1.0."
+        );
+
+        let plan = "projects:\n  - name: a\n    value:\n      distribution: pert\n      min: 5\n      likely: 1\n      max: 9\n";
+        let error = parse(plan, Format::Yaml).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4: projects[0].value: 5-1-9 isn't a valid estimate."
+        )
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let plan = "[[costs]]\nname = \"Rent\"\nammount = 100\n";
+        let error = parse(plan, Format::Toml).unwrap_err();
+        assert!(matches!(error, PlanError::Invalid { line: Some(3), .. }))
+    }
+
+    #[test]
+    fn builder_errors_point_at_the_member() {
+        let plan =
+            "costs:\n  - name: Rent\n    start: 2022-09-01\n    end: 2022-08-01\n  - name: Rent\n";
+        assert_eq!(
+            parse(plan, Format::Yaml).unwrap_err().to_string(),
+            "line 2: \"Rent\": Cost ends before it starts."
+        );
+
+        let plan = "[[projects]]\nname = \"p1\"\n\n[[costs]]\nname = \"p1\"\n";
+        assert_eq!(
+            parse(plan, Format::Toml).unwrap_err().to_string(),
+            "line 4: \"p1\": Portfolio already has something called \"p1\"."
+        );
+
+        // Alice is on the team too, listed first.
+        let plan = "team:\n  - name: Alice\n    capacity: 1\n    skills: [rust]\ncosts:\n  - name: Alice\n    amount: 0\n";
+        assert_eq!(
+            parse(plan, Format::Yaml).unwrap_err().to_string(),
+            "line 6: \"Alice\": Cost has no amount."
+        )
+    }
+
+    #[test]
+    fn relationship_errors_point_at_the_project() {
+        let plan =
+            "projects:\n  - name: a\n    depends_on: [b]\n  - name: b\n    depends_on: [a]\n";
+        assert_eq!(
+            parse(plan, Format::Yaml).unwrap_err().to_string(),
+            "line 2: \"a\": Projects depend on each other in a loop: a -> b -> a."
+        );
+
+        let plan = "[[projects]]\nname = \"a\"\n\n[[projects]]\nname = \"b\"\nexcludes = [\"c\"]\n";
+        assert_eq!(
+            parse(plan, Format::Toml).unwrap_err().to_string(),
+            "line 4: \"b\": Project \"b\" refers to \"c\", which isn't a project in the portfolio."
+        )
+    }
+
+    #[test]
+    fn lags_are_bounded() {
+        for lag in ["9223372036854775807", "100000000", "-100000"] {
+            let plan = format!(
+                "[[projects]]\nname = \"a\"\n\n[[projects]]\nname = \"b\"\nstarts_after = [{{ project = \"a\", lag_days = {} }}]\n",
+                lag
+            );
+            let error = parse(&plan, Format::Toml).unwrap_err().to_string();
+            assert!(error.starts_with("line 6:"), "{}", error);
+            assert!(
+                error.contains("must be between -36525 and 36525"),
+                "{}",
+                error
+            )
+        }
+    }
+
+    #[test]
+    fn durations_and_delays_are_bounded() {
+        for (field, value, bounds) in [
+            ("duration_days", "1e15", "between 0 and 36525"),
+            ("duration_days", "-3", "between 0 and 36525"),
+            ("duration_weeks", "100000000", "between 0 and 5217"),
+            ("duration_weeks", "-1", "between 0 and 5217"),
+            ("start_delay", "1e9", "between 0 and 36525"),
+            ("start_delay", "-7", "between 0 and 36525"),
+        ] {
+            let plan = format!(
+                "[[projects]]\nname = \"a\"\n\n[[projects]]\nname = \"b\"\n{} = {}\n",
+                field, value
+            );
+            let error = parse(&plan, Format::Toml).unwrap_err().to_string();
+            assert!(error.starts_with("line 6:"), "{}", error);
+            assert!(error.contains(bounds), "{}", error);
+
+            let plan = format!("projects:\n  - name: a\n    {}: {}\n", field, value);
+            let error = parse(&plan, Format::Yaml).unwrap_err().to_string();
+            assert!(error.starts_with("line 3:"), "{}", error);
+            assert!(error.contains(bounds), "{}", error)
+        }
+    }
+}
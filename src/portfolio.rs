@@ -0,0 +1,1281 @@
+use crate::{
+    costs::Cost,
+    expertise::Team,
+    money::{Currency, ExchangeRates, MoneyError},
+    projects::{Predecessor, Project},
+    simulation::SimulationError,
+    stats::normal_cdf,
+    traits::{Breakdown, Contribution, Sample, TimeBound},
+};
+use chrono::{prelude::*, Duration};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+#[derive(PartialEq, Debug)]
+pub enum PortfolioError {
+    /// Projects that depend on each other in a loop, starting and ending with the same one.
+    Cycle(Vec<String>),
+    DuplicateName(String),
+    NotFound(String),
+    /// A Project or Cost is worth too much to convert to the reporting currency.
+    TooLarge(String),
+    /// A Project or Cost (`name`) is in a currency there's no exchange rate for.
+    UnknownCurrency {
+        name: String,
+        currency: Currency,
+    },
+    /// A Project (`from`) refers to another (`to`) that isn't in the portfolio.
+    UnknownReference {
+        from: String,
+        to: String,
+    },
+}
+
+impl std::error::Error for PortfolioError {}
+impl std::fmt::Display for PortfolioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            PortfolioError::Cycle(names) => {
+                write!(
+                    f,
+                    "Projects depend on each other in a loop: {}.",
+                    names.join(" -> ")
+                )
+            }
+            PortfolioError::DuplicateName(name) => {
+                write!(f, "Portfolio already has something called \"{}\".", name)
+            }
+            PortfolioError::NotFound(name) => {
+                write!(f, "Portfolio has nothing called \"{}\".", name)
+            }
+            PortfolioError::TooLarge(name) => {
+                write!(f, "\"{}\" is too large to convert.", name)
+            }
+            PortfolioError::UnknownCurrency { name, currency } => {
+                write!(
+                    f,
+                    "\"{}\" is in {}, but there's no exchange rate for it.",
+                    name, currency
+                )
+            }
+            PortfolioError::UnknownReference { from, to } => {
+                write!(
+                    f,
+                    "Project \"{}\" refers to \"{}\", which isn't a project in the portfolio.",
+                    from, to
+                )
+            }
+        }
+    }
+}
+
+/// # Portfolio
+/// Every Project and Cost we're planning for,
+/// the Team to staff the Projects, and the exchange rates
+/// for reporting them all in one currency.
+/// Names are unique across the whole portfolio.
+#[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(try_from = "PortfolioInput"))]
+pub struct Portfolio {
+    /// Every Cost in the reporting currency, converted when it's added
+    /// or the rates change, so trials never convert.
+    #[cfg_attr(feature = "json", serde(skip))]
+    converted_costs: Vec<Cost>,
+    /// Every Project in the reporting currency, like `converted_costs`.
+    #[cfg_attr(feature = "json", serde(skip))]
+    converted_projects: Vec<Project>,
+    costs: Vec<Cost>,
+    exchange_rates: ExchangeRates,
+    projects: Vec<Project>,
+    team: Team,
+}
+
+/// Members as written in JSON, before their names are checked.
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct PortfolioInput {
+    costs: Vec<Cost>,
+    exchange_rates: ExchangeRates,
+    projects: Vec<Project>,
+    team: Team,
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<PortfolioInput> for Portfolio {
+    type Error = PortfolioError;
+
+    fn try_from(input: PortfolioInput) -> Result<Self, Self::Error> {
+        let mut portfolio = Portfolio::default();
+        portfolio.set_exchange_rates(input.exchange_rates)?;
+        for project in input.projects {
+            portfolio.add_project(project)?;
+        }
+        for cost in input.costs {
+            portfolio.add_cost(cost)?;
+        }
+        portfolio.set_team(input.team);
+        portfolio.check_relationships()?;
+        Ok(portfolio)
+    }
+}
+
+impl Portfolio {
+    /// Adds a Project to the portfolio.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::portfolio::{Portfolio, PortfolioError};
+    /// use hallo::projects::ProjectBuilder;
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// let p1 = ProjectBuilder::default().name("p1").build().unwrap();
+    /// assert!(portfolio.add_project(p1.clone()).is_ok());
+    /// assert_eq!(
+    ///     portfolio.add_project(p1),
+    ///     Err(PortfolioError::DuplicateName("p1".into()))
+    /// )
+    /// ```
+    pub fn add_project(&mut self, project: Project) -> Result<(), PortfolioError> {
+        self.check_name_is_free(&project.name)?;
+        let converted = convert_project(&self.exchange_rates, &project)?;
+        self.converted_projects.push(converted);
+        self.projects.push(project);
+        Ok(())
+    }
+
+    /// Adds a Cost to the portfolio.
+    pub fn add_cost(&mut self, cost: Cost) -> Result<(), PortfolioError> {
+        self.check_name_is_free(&cost.name)?;
+        let converted = convert_cost(&self.exchange_rates, &cost)?;
+        self.converted_costs.push(converted);
+        self.costs.push(cost);
+        Ok(())
+    }
+
+    /// Removes a Project from the portfolio, returning it.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::portfolio::Portfolio;
+    /// use hallo::projects::ProjectBuilder;
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// portfolio.add_project(ProjectBuilder::default().name("p1").build().unwrap()).unwrap();
+    /// let p1 = portfolio.remove_project("p1").unwrap();
+    /// assert_eq!(p1.name, "p1");
+    /// assert!(portfolio.project("p1").is_none())
+    /// ```
+    pub fn remove_project(&mut self, name: &str) -> Result<Project, PortfolioError> {
+        match self.projects.iter().position(|p| p.name == name) {
+            Some(index) => {
+                self.converted_projects.remove(index);
+                Ok(self.projects.remove(index))
+            }
+            None => Err(PortfolioError::NotFound(name.into())),
+        }
+    }
+
+    /// Removes a Cost from the portfolio, returning it.
+    pub fn remove_cost(&mut self, name: &str) -> Result<Cost, PortfolioError> {
+        match self.costs.iter().position(|c| c.name == name) {
+            Some(index) => {
+                self.converted_costs.remove(index);
+                Ok(self.costs.remove(index))
+            }
+            None => Err(PortfolioError::NotFound(name.into())),
+        }
+    }
+
+    /// Finds a Project by name.
+    pub fn project(&self, name: &str) -> Option<&Project> {
+        self.projects.iter().find(|p| p.name == name)
+    }
+
+    /// Finds a Cost by name.
+    pub fn cost(&self, name: &str) -> Option<&Cost> {
+        self.costs.iter().find(|c| c.name == name)
+    }
+
+    /// Returns every Project, in the order they were added.
+    pub fn projects(&self) -> &[Project] {
+        &self.projects
+    }
+
+    /// Returns every Cost, in the order they were added.
+    pub fn costs(&self) -> &[Cost] {
+        &self.costs
+    }
+
+    /// Sets the Team staffing the Projects.
+    /// In every trial, Projects that happen are pushed back until the team
+    /// has the people for them, in the order they were added.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::expertise::{Expert, Team};
+    /// use hallo::portfolio::Portfolio;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::traits::{Sample, TimeBound};
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// for name in ["p1", "p2"] {
+    ///     let p = ProjectBuilder::default()
+    ///         .name(name)
+    ///         .probability(1.0)
+    ///         .start_date(&Utc.ymd(2022, 8, 1))
+    ///         .requires("rust", 1.0)
+    ///         .build()
+    ///         .unwrap();
+    ///     portfolio.add_project(p).unwrap();
+    /// }
+    /// portfolio.set_team(Team {
+    ///     members: vec![Expert { capacity: 1.0, name: "Alice".into(), skills: vec!["rust".into()] }],
+    /// });
+    /// let outcome = portfolio.sample(&mut rand::thread_rng());
+    /// assert_eq!(outcome.project("p2").unwrap().start_date(), &Utc.ymd(2022, 8, 29))
+    /// ```
+    pub fn set_team(&mut self, team: Team) {
+        self.team = team;
+    }
+
+    /// Returns the Team staffing the Projects.
+    pub fn team(&self) -> &Team {
+        &self.team
+    }
+
+    /// Sets the exchange rates for reporting every Project and Cost
+    /// in one currency. Contributions, and sampled outcomes,
+    /// are in the reporting currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::money::{Currency, ExchangeRates};
+    /// use hallo::portfolio::{Portfolio, PortfolioError};
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::recognition::Recognition;
+    /// use hallo::traits::Contribution;
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// let p = ProjectBuilder::default()
+    ///     .name("p1")
+    ///     .value(1000)
+    ///     .currency(Currency::EUR)
+    ///     .recognition(Recognition::Start)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     portfolio.add_project(p.clone()),
+    ///     Err(PortfolioError::UnknownCurrency { name: "p1".into(), currency: Currency::EUR })
+    /// );
+    /// let mut rates = ExchangeRates::new(Currency::GBP);
+    /// rates.add_rate(Currency::EUR, 0.85).unwrap();
+    /// portfolio.set_exchange_rates(rates).unwrap();
+    /// portfolio.add_project(p).unwrap();
+    /// assert_eq!(portfolio.get_contribution_on(&Utc.ymd(2022, 8, 2)), 85000)
+    /// ```
+    pub fn set_exchange_rates(&mut self, rates: ExchangeRates) -> Result<(), PortfolioError> {
+        let converted_projects = self
+            .projects
+            .iter()
+            .map(|p| convert_project(&rates, p))
+            .collect::<Result<_, _>>()?;
+        let converted_costs = self
+            .costs
+            .iter()
+            .map(|c| convert_cost(&rates, c))
+            .collect::<Result<_, _>>()?;
+        self.converted_projects = converted_projects;
+        self.converted_costs = converted_costs;
+        self.exchange_rates = rates;
+        Ok(())
+    }
+
+    /// Returns the exchange rates for reporting in one currency.
+    pub fn exchange_rates(&self) -> &ExchangeRates {
+        &self.exchange_rates
+    }
+
+    /// Returns the Projects active at some point between `from` (inclusive)
+    /// and `until` (exclusive).
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::portfolio::Portfolio;
+    /// use hallo::projects::ProjectBuilder;
+    /// use chrono::prelude::*;
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// for (name, month) in [("p1", 1), ("p2", 6)] {
+    ///     let p = ProjectBuilder::default()
+    ///         .name(name)
+    ///         .start_date(&Utc.ymd(2022, month, 1))
+    ///         .build()
+    ///         .unwrap();
+    ///     portfolio.add_project(p).unwrap();
+    /// }
+    /// let names: Vec<_> = portfolio
+    ///     .projects_between(&Utc.ymd(2022, 5, 1), &Utc.ymd(2022, 7, 1))
+    ///     .map(|p| p.name.as_str())
+    ///     .collect();
+    /// assert_eq!(names, vec!["p2"])
+    /// ```
+    pub fn projects_between(
+        &self,
+        from: &Date<Utc>,
+        until: &Date<Utc>,
+    ) -> impl Iterator<Item = &Project> + '_ {
+        let (from, until) = (*from, *until);
+        self.projects
+            .iter()
+            .filter(move |p| p.overlaps(&from, &until))
+    }
+
+    /// Checks that every Project another depends on, excludes or starts after
+    /// is in the portfolio, and that no Projects depend on each other in a loop.
+    /// Trials still run otherwise, but Projects depending on something missing,
+    /// or on themselves, never happen.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::portfolio::{Portfolio, PortfolioError};
+    /// use hallo::projects::ProjectBuilder;
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// for (name, dependency) in [("a", "b"), ("b", "c"), ("c", "a")] {
+    ///     let p = ProjectBuilder::default().name(name).depends_on(dependency).build().unwrap();
+    ///     portfolio.add_project(p).unwrap();
+    /// }
+    /// assert_eq!(
+    ///     portfolio.check_relationships(),
+    ///     Err(PortfolioError::Cycle(vec!["a".into(), "b".into(), "c".into(), "a".into()]))
+    /// )
+    /// ```
+    pub fn check_relationships(&self) -> Result<(), PortfolioError> {
+        for project in &self.projects {
+            for other in project
+                .dependencies()
+                .iter()
+                .chain(project.exclusions())
+                .chain(project.predecessors().iter().map(|p| &p.project))
+            {
+                if self.project(other).is_none() {
+                    return Err(PortfolioError::UnknownReference {
+                        from: project.name.clone(),
+                        to: other.clone(),
+                    });
+                }
+            }
+        }
+        let mut finished = vec![false; self.projects.len()];
+        let mut path = vec![];
+        for index in 0..self.projects.len() {
+            self.find_cycle(index, &mut path, &mut finished)?;
+        }
+        Ok(())
+    }
+
+    /// Walks the dependencies depth first from `index`,
+    /// failing when it comes back to a Project still on the `path`.
+    fn find_cycle(
+        &self,
+        index: usize,
+        path: &mut Vec<usize>,
+        finished: &mut [bool],
+    ) -> Result<(), PortfolioError> {
+        if finished[index] {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|i| *i == index) {
+            let mut names: Vec<String> = path[start..]
+                .iter()
+                .map(|i| self.projects[*i].name.clone())
+                .collect();
+            names.push(self.projects[index].name.clone());
+            return Err(PortfolioError::Cycle(names));
+        }
+        path.push(index);
+        let project = &self.projects[index];
+        let predecessors = project.predecessors().iter().map(|p| &p.project);
+        for dependency in project.dependencies().iter().chain(predecessors) {
+            if let Some(next) = self.position(dependency) {
+                self.find_cycle(next, path, finished)?;
+            }
+        }
+        path.pop();
+        finished[index] = true;
+        Ok(())
+    }
+
+    fn position(&self, project: &str) -> Option<usize> {
+        self.projects.iter().position(|p| p.name == project)
+    }
+
+    /// Whether the Project at `index` can happen, once every Project
+    /// it depends on is decided: all of them happened,
+    /// and nothing that happened rules it out.
+    fn eligible(&self, index: usize, decided: &[Option<Option<Project>>]) -> Option<bool> {
+        let project = &self.projects[index];
+        let mut met = true;
+        for dependency in project.dependencies() {
+            match self.position(dependency).map(|i| &decided[i]) {
+                Some(None) => return None,
+                Some(Some(Some(_))) => {}
+                Some(Some(None)) | None => met = false,
+            }
+        }
+        let excluded = decided.iter().flatten().flatten().any(|other| {
+            project.exclusions().contains(&other.name) || other.exclusions().contains(&project.name)
+        });
+        Some(met && !excluded)
+    }
+
+    /// Decides a whole group of alternatives at once, picking the Project
+    /// that happens from the members that can, weighted by their probabilities.
+    /// Waits until every member can be decided, unless `wait` is false,
+    /// when members still waiting on others are passed over.
+    /// Returns whether the group was decided.
+    fn decide_alternatives<R: Rng + ?Sized>(
+        &self,
+        group: &str,
+        decided: &mut [Option<Option<Project>>],
+        wait: bool,
+        rng: &mut R,
+    ) -> bool {
+        let members: Vec<usize> = (0..self.projects.len())
+            .filter(|i| self.projects[*i].group() == Some(group))
+            .collect();
+        let mut eligible = vec![];
+        for index in &members {
+            match self.eligible(*index, decided) {
+                Some(true) => eligible.push(*index),
+                Some(false) => {}
+                None if wait => return false,
+                None => {}
+            }
+        }
+        let total: f64 = eligible
+            .iter()
+            .map(|i| self.projects[*i].probability())
+            .sum();
+        let mut pick = rng.gen::<f64>() * total;
+        let winner = match total > 0.0 {
+            true => eligible
+                .iter()
+                .copied()
+                .find(|i| {
+                    pick -= self.projects[*i].probability();
+                    pick < 0.0
+                })
+                .or_else(|| eligible.last().copied()),
+            false => None,
+        };
+        for index in members {
+            decided[index] = Some(
+                (Some(index) == winner)
+                    .then(|| self.converted_projects[index].sample_happened(rng)),
+            );
+        }
+        true
+    }
+
+    /// Draws how well every correlation group does, as a standard normal,
+    /// in the order the groups are first listed.
+    fn sample_group_factors<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<(&str, f64)> {
+        let mut factors: Vec<(&str, f64)> = vec![];
+        for correlation in self.projects.iter().filter_map(Project::correlation) {
+            if !factors.iter().any(|(group, _)| *group == correlation.group) {
+                factors.push((&correlation.group, rng.sample(StandardNormal)));
+            }
+        }
+        factors
+    }
+
+    /// Rolls the dice on a Project happening on its own.
+    /// Correlated Projects mix their group's draw with their own,
+    /// so their chance of happening still matches their probability.
+    fn roll<R: Rng + ?Sized>(project: &Project, factors: &[(&str, f64)], rng: &mut R) -> bool {
+        let correlation = match project.correlation() {
+            Some(correlation) => correlation,
+            None => return rng.gen_bool(project.probability()),
+        };
+        let factor = factors
+            .iter()
+            .find(|(group, _)| *group == correlation.group)
+            .map_or(0.0, |(_, factor)| *factor);
+        let own: f64 = rng.sample(StandardNormal);
+        let draw = correlation.strength.sqrt() * factor + (1.0 - correlation.strength).sqrt() * own;
+        project.probability() >= 1.0 || normal_cdf(draw) < project.probability()
+    }
+
+    /// Moves the Projects that happened, and start relative to others,
+    /// to when they can start: after their predecessors end,
+    /// if they wait for the team, once nothing they'd share people with is running,
+    /// and once the Team has the people for them.
+    /// Projects that would start past the last date there is don't happen.
+    fn schedule(&self, outcomes: &mut [Option<Project>]) {
+        let mut scheduled: Vec<usize> = vec![];
+        let mut settled = vec![false; self.projects.len()];
+        // Predecessors are settled first. Anything left waits on itself in a loop.
+        let mut progress = true;
+        while progress {
+            progress = false;
+            for (index, project) in self.projects.iter().enumerate() {
+                let ready = project.predecessors().iter().all(|predecessor| {
+                    self.position(&predecessor.project)
+                        .is_none_or(|i| settled[i])
+                });
+                if settled[index] || !ready {
+                    continue;
+                }
+                settled[index] = true;
+                progress = true;
+                let outcome = match &outcomes[index] {
+                    Some(outcome) => outcome,
+                    None => continue,
+                };
+
+                let mut start = Some(*outcome.start_date());
+                for predecessor in project.predecessors() {
+                    let before = self.position(&predecessor.project).map(|i| &outcomes[i]);
+                    if let Some(Some(before)) = before {
+                        let after = (predecessor.lag_days.abs() <= Predecessor::MAX_LAG_DAYS)
+                            .then(|| Duration::days(predecessor.lag_days))
+                            .and_then(|lag| before.end_date().checked_add_signed(lag));
+                        start = start.zip(after).map(|(start, after)| start.max(after));
+                    }
+                }
+                let mut start = match start {
+                    Some(start) => start,
+                    None => {
+                        outcomes[index] = None;
+                        continue;
+                    }
+                };
+                if project.waits_for_team() {
+                    let duration = outcome.duration();
+                    while let Some(end) = scheduled
+                        .iter()
+                        .filter_map(|i| outcomes[*i].as_ref())
+                        .filter(|other| {
+                            project.shares_skills_with(other)
+                                && *other.start_date() < start + duration
+                                && *other.end_date() > start
+                        })
+                        .map(|other| *other.end_date())
+                        .max()
+                    {
+                        start = end;
+                    }
+                }
+                if !self.team.is_empty() {
+                    let others: Vec<&Project> = scheduled
+                        .iter()
+                        .filter_map(|i| outcomes[*i].as_ref())
+                        .collect();
+                    start = self.team.earliest_start(outcome, &start, &others);
+                }
+                if let Some(outcome) = &mut outcomes[index] {
+                    outcome.move_to(&start);
+                }
+                scheduled.push(index);
+            }
+        }
+    }
+
+    /// Checks if the portfolio is empty.
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty() && self.costs.is_empty()
+    }
+
+    fn check_name_is_free(&self, name: &str) -> Result<(), PortfolioError> {
+        if self.project(name).is_some() || self.cost(name).is_some() {
+            return Err(PortfolioError::DuplicateName(name.into()));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Portfolio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for project in &self.projects {
+            writeln!(f, "{}", project)?;
+        }
+        for cost in &self.costs {
+            writeln!(f, "{}", cost)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the Project with its value converted to the reporting currency.
+fn convert_project(rates: &ExchangeRates, project: &Project) -> Result<Project, PortfolioError> {
+    project
+        .converted(rates)
+        .map_err(|e| conversion_error(&project.name, e))
+}
+
+/// Returns the Cost with its amount converted to the reporting currency.
+fn convert_cost(rates: &ExchangeRates, cost: &Cost) -> Result<Cost, PortfolioError> {
+    cost.converted(rates)
+        .map_err(|e| conversion_error(&cost.name, e))
+}
+
+/// Explains why a Project or Cost (`name`) can't be converted to the reporting currency.
+fn conversion_error(name: &str, error: MoneyError) -> PortfolioError {
+    match error {
+        MoneyError::UnknownRate(currency) => PortfolioError::UnknownCurrency {
+            name: name.into(),
+            currency,
+        },
+        _ => PortfolioError::TooLarge(name.into()),
+    }
+}
+
+impl Contribution for Portfolio {
+    /// Returns the net contribution of every member for a given day,
+    /// in hundredths of the reporting currency.
+    ///
+    /// ### Example
+    /// ```
+    /// use hallo::costs::CostBuilder;
+    /// use hallo::portfolio::Portfolio;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::traits::Contribution;
+    /// use chrono::prelude::*;
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// let p = ProjectBuilder::default()
+    ///     .value(2800)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .build()
+    ///     .unwrap();
+    /// let c = CostBuilder::default()
+    ///     .amount(30)
+    ///     .start_date(&Utc.ymd(2022, 8, 2))
+    ///     .build()
+    ///     .unwrap();
+    /// portfolio.add_project(p).unwrap();
+    /// portfolio.add_cost(c).unwrap();
+    /// assert_eq!(portfolio.get_contribution_on(&Utc.ymd(2022, 8, 2)), 7000)
+    /// ```
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        // Values were converted before they're spread over the days,
+        // as in every sampled outcome, so rounding doesn't add up.
+        self.converted_projects.get_contribution_on(date)
+            + self.converted_costs.get_contribution_on(date)
+    }
+
+    /// Everything is converted to the reporting currency.
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        Ok(Some(self.exchange_rates.reporting()))
+    }
+}
+
+impl Breakdown for Portfolio {
+    /// Returns every Project, then every Cost.
+    fn parts(&self) -> Vec<(&str, &dyn Contribution)> {
+        let mut parts = self.projects.parts();
+        parts.extend(self.costs.parts());
+        parts
+    }
+}
+
+impl Sample for Portfolio {
+    type Outcome = Portfolio;
+
+    /// Checks the Projects only refer to each other,
+    /// and don't depend on each other in a loop.
+    ///
+    /// ### Example
+    /// ```
+    /// use hallo::portfolio::{Portfolio, PortfolioError};
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::{Simulation, SimulationError};
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// let p = ProjectBuilder::default().name("a").depends_on("b").build().unwrap();
+    /// portfolio.add_project(p).unwrap();
+    /// assert_eq!(
+    ///     Simulation::new(&portfolio).run(),
+    ///     Err(SimulationError::Portfolio(PortfolioError::UnknownReference {
+    ///         from: "a".into(),
+    ///         to: "b".into()
+    ///     }))
+    /// )
+    /// ```
+    fn check(&self) -> Result<(), SimulationError> {
+        Ok(self.check_relationships()?)
+    }
+
+    /// Rolls the dice on every Project.
+    /// Returns the portfolio as it turned out: only the Projects
+    /// that happened, and every Cost, all converted to the reporting currency.
+    ///
+    /// Relationships between Projects hold in every trial:
+    /// - a Project only happens if every Project it depends on happened,
+    ///   so its probability is its chance once they have;
+    /// - of two Projects that exclude each other, the one decided first
+    ///   rules out the other. That's the one listed first, unless it waits
+    ///   for a Project it depends on that's listed after the other;
+    /// - exactly one Project from each group of alternatives happens,
+    ///   picked from the ones whose dependencies happened and that
+    ///   nothing rules out, unless that leaves none;
+    /// - correlated Projects tend to happen, or not, together;
+    /// - Projects starting after others, or when the team is free,
+    ///   move back when those run late;
+    /// - Projects wait until the Team has the people for them.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Outcome {
+        let factors = self.sample_group_factors(rng);
+        let mut decided: Vec<Option<Option<Project>>> = vec![None; self.projects.len()];
+        // Dependencies are settled before the Projects depending on them,
+        // and groups of alternatives once every member's dependencies are.
+        // If that stalls, because alternatives wait on each other, the first
+        // group with a member ready is settled without the ones still waiting.
+        // Anything left undecided depends on itself in a loop and never happens.
+        loop {
+            let mut progress = false;
+            for (index, project) in self.projects.iter().enumerate() {
+                if decided[index].is_some() {
+                    continue;
+                }
+                progress |= match project.group() {
+                    Some(group) => self.decide_alternatives(group, &mut decided, true, rng),
+                    None => match self.eligible(index, &decided) {
+                        Some(eligible) => {
+                            let won = eligible && Self::roll(project, &factors, rng);
+                            decided[index] = Some(
+                                won.then(|| self.converted_projects[index].sample_happened(rng)),
+                            );
+                            true
+                        }
+                        None => false,
+                    },
+                };
+            }
+            if progress {
+                continue;
+            }
+            let stalled = (0..self.projects.len()).find(|i| {
+                decided[*i].is_none()
+                    && self.projects[*i].group().is_some()
+                    && self.eligible(*i, &decided).is_some()
+            });
+            match stalled.and_then(|i| self.projects[i].group()) {
+                Some(group) => {
+                    self.decide_alternatives(group, &mut decided, false, rng);
+                }
+                None => break,
+            }
+        }
+        let mut outcomes: Vec<Option<Project>> = decided.into_iter().map(Option::flatten).collect();
+        self.schedule(&mut outcomes);
+        let projects: Vec<Project> = outcomes.into_iter().flatten().collect();
+        Portfolio {
+            converted_costs: self.converted_costs.clone(),
+            converted_projects: projects.clone(),
+            costs: self.converted_costs.clone(),
+            exchange_rates: ExchangeRates::new(self.exchange_rates.reporting()),
+            projects,
+            team: self.team.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::costs::CostBuilder;
+    use crate::money::Money;
+    use crate::projects::ProjectBuilder;
+
+    #[test]
+    fn members_too_large_to_convert_are_refused() {
+        let mut rates = ExchangeRates::new(Currency::GBP);
+        rates.add_rate(Currency::EUR, 1000.0).unwrap();
+        let mut portfolio = Portfolio::default();
+        portfolio.set_exchange_rates(rates).unwrap();
+        let cost = CostBuilder::default()
+            .name("c1")
+            .amount(i64::MAX as u64 / 1000)
+            .currency(Currency::EUR);
+        assert_eq!(
+            portfolio.add_cost(cost.build().unwrap()),
+            Err(PortfolioError::TooLarge("c1".into()))
+        );
+        let project = ProjectBuilder::default()
+            .name("p1")
+            .value_estimate(crate::estimate::Estimate::Uniform {
+                min: 0.0,
+                max: 1e15,
+            })
+            .currency(Currency::EUR);
+        assert_eq!(
+            portfolio.add_project(project.build().unwrap()),
+            Err(PortfolioError::TooLarge("p1".into()))
+        )
+    }
+
+    #[test]
+    fn converted_contributions_add_up_to_the_converted_value() {
+        let mut rates = ExchangeRates::new(Currency::GBP);
+        rates.add_rate(Currency::EUR, 0.855).unwrap();
+        let mut portfolio = Portfolio::default();
+        portfolio.set_exchange_rates(rates).unwrap();
+        let p = ProjectBuilder::default()
+            .value(1000)
+            .currency(Currency::EUR)
+            .probability(1.0)
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .duration_weeks(1)
+            .build()
+            .unwrap();
+        portfolio.add_project(p).unwrap();
+        let total = |portfolio: &Portfolio| -> i64 {
+            (1..=7)
+                .map(|day| portfolio.get_contribution_on(&Utc.ymd(2022, 8, day + 1)))
+                .sum()
+        };
+        assert_eq!(total(&portfolio), 85500);
+        assert_eq!(total(&portfolio.sample(&mut rand::thread_rng())), 85500)
+    }
+
+    #[test]
+    fn conversions_follow_the_members() {
+        let mut rates = ExchangeRates::new(Currency::GBP);
+        rates.add_rate(Currency::EUR, 0.5).unwrap();
+        let mut portfolio = Portfolio::default();
+        portfolio.set_exchange_rates(rates).unwrap();
+        for (name, currency) in [("p1", Currency::EUR), ("p2", Currency::GBP)] {
+            let p = ProjectBuilder::default()
+                .name(name)
+                .value(1000)
+                .currency(currency)
+                .recognition(crate::recognition::Recognition::Start)
+                .start_date(&Utc.ymd(2022, 8, 1))
+                .build()
+                .unwrap();
+            portfolio.add_project(p).unwrap();
+        }
+        let day = Utc.ymd(2022, 8, 2);
+        assert_eq!(portfolio.get_contribution_on(&day), 150000);
+        portfolio.remove_project("p1").unwrap();
+        assert_eq!(portfolio.get_contribution_on(&day), 100000);
+
+        let mut rates = ExchangeRates::new(Currency::EUR);
+        rates.add_rate(Currency::GBP, 2.0).unwrap();
+        portfolio.set_exchange_rates(rates).unwrap();
+        assert_eq!(portfolio.get_contribution_on(&day), 200000)
+    }
+
+    #[test]
+    fn names_are_unique_across_members() {
+        let mut portfolio = Portfolio::default();
+        let p = ProjectBuilder::default().name("shared").build().unwrap();
+        let c = CostBuilder::default().name("shared").build().unwrap();
+        portfolio.add_project(p).unwrap();
+        assert_eq!(
+            portfolio.add_cost(c),
+            Err(PortfolioError::DuplicateName("shared".into()))
+        )
+    }
+
+    #[test]
+    fn remove_missing() {
+        let mut portfolio = Portfolio::default();
+        assert_eq!(
+            portfolio.remove_cost("nope"),
+            Err(PortfolioError::NotFound("nope".into()))
+        )
+    }
+
+    #[test]
+    fn sampled_portfolio_keeps_certain_members() {
+        let mut portfolio = Portfolio::default();
+        let certain = ProjectBuilder::default()
+            .name("certain")
+            .probability(1.0)
+            .build()
+            .unwrap();
+        let never = ProjectBuilder::default()
+            .name("never")
+            .probability(0.0)
+            .build()
+            .unwrap();
+        portfolio.add_project(certain).unwrap();
+        portfolio.add_project(never).unwrap();
+        portfolio
+            .add_cost(CostBuilder::default().build().unwrap())
+            .unwrap();
+
+        let outcome = portfolio.sample(&mut rand::thread_rng());
+        assert!(outcome.project("certain").is_some());
+        assert!(outcome.project("never").is_none());
+        assert_eq!(outcome.costs().len(), 1)
+    }
+
+    fn portfolio(projects: Vec<ProjectBuilder>) -> Portfolio {
+        let mut portfolio = Portfolio::default();
+        for project in projects {
+            portfolio.add_project(project.build().unwrap()).unwrap();
+        }
+        portfolio.check_relationships().unwrap();
+        portfolio
+    }
+
+    fn names(outcome: &Portfolio) -> Vec<&str> {
+        outcome.projects().iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn dependents_need_their_dependencies() {
+        // Listed first, so it has to wait for its dependency.
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default()
+                .name("phase 2")
+                .probability(1.0)
+                .depends_on("phase 1"),
+            ProjectBuilder::default().name("phase 1").probability(0.5),
+        ]);
+        let mut rng = rand::thread_rng();
+        let outcomes: Vec<_> = (0..200).map(|_| portfolio.sample(&mut rng)).collect();
+        for outcome in &outcomes {
+            let names = names(outcome);
+            assert!(names == ["phase 2", "phase 1"] || names.is_empty())
+        }
+        let both = outcomes.iter().filter(|o| !o.projects().is_empty()).count();
+        assert!((50..150).contains(&both))
+    }
+
+    #[test]
+    fn exclusions_work_both_ways() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default().name("a").probability(1.0),
+            ProjectBuilder::default()
+                .name("b")
+                .probability(1.0)
+                .excludes("a"),
+        ]);
+        assert_eq!(names(&portfolio.sample(&mut rand::thread_rng())), ["a"])
+    }
+
+    #[test]
+    fn exactly_one_alternative_happens() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default()
+                .name("bid a")
+                .probability(0.3)
+                .one_of("tender"),
+            ProjectBuilder::default()
+                .name("bid b")
+                .probability(0.1)
+                .one_of("tender"),
+            ProjectBuilder::default().name("other").probability(0.0),
+        ]);
+        let mut rng = rand::thread_rng();
+        let mut wins_for_a = 0;
+        for _ in 0..400 {
+            let outcome = portfolio.sample(&mut rng);
+            assert_eq!(outcome.projects().len(), 1);
+            if outcome.project("bid a").is_some() {
+                wins_for_a += 1;
+            }
+        }
+        // Weighted 3 to 1.
+        assert!((250..350).contains(&wins_for_a))
+    }
+
+    #[test]
+    fn alternatives_are_picked_from_those_that_can_happen() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default().name("framework").probability(0.0),
+            ProjectBuilder::default().name("incumbent").probability(1.0),
+            ProjectBuilder::default()
+                .name("bid a")
+                .probability(0.9)
+                .one_of("tender")
+                .depends_on("framework"),
+            ProjectBuilder::default()
+                .name("bid b")
+                .probability(0.9)
+                .one_of("tender")
+                .excludes("incumbent"),
+            ProjectBuilder::default()
+                .name("bid c")
+                .probability(0.1)
+                .one_of("tender"),
+        ]);
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert_eq!(names(&portfolio.sample(&mut rng)), ["incumbent", "bid c"])
+        }
+    }
+
+    #[test]
+    fn alternatives_waiting_on_each_other_still_pick_one() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default()
+                .name("bid a")
+                .one_of("tender")
+                .depends_on("bid b"),
+            ProjectBuilder::default().name("bid b").one_of("tender"),
+        ]);
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert_eq!(names(&portfolio.sample(&mut rng)), ["bid b"])
+        }
+    }
+
+    fn both_happen(portfolio: &Portfolio, trials: usize) -> usize {
+        let mut rng = rand::thread_rng();
+        (0..trials)
+            .filter(|_| portfolio.sample(&mut rng).projects().len() == 2)
+            .count()
+    }
+
+    #[test]
+    fn fully_correlated_projects_happen_together() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default()
+                .name("a")
+                .probability(0.5)
+                .correlated("acme", 1.0),
+            ProjectBuilder::default()
+                .name("b")
+                .probability(0.5)
+                .correlated("acme", 1.0),
+        ]);
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            assert_ne!(portfolio.sample(&mut rng).projects().len(), 1)
+        }
+    }
+
+    #[test]
+    fn correlation_keeps_each_chance() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default()
+                .name("a")
+                .probability(0.5)
+                .correlated("acme", 0.5),
+            ProjectBuilder::default()
+                .name("b")
+                .probability(0.5)
+                .correlated("acme", 0.5),
+        ]);
+        let mut rng = rand::thread_rng();
+        let a = (0..2000)
+            .filter(|_| portfolio.sample(&mut rng).project("a").is_some())
+            .count();
+        assert!((900..1100).contains(&a));
+        // Both happen a third of the time, rather than a quarter when independent.
+        assert!((580..760).contains(&both_happen(&portfolio, 2000)))
+    }
+
+    #[test]
+    fn successors_past_the_last_date_do_not_happen() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default()
+                .name("p1")
+                .probability(1.0)
+                .start_date(&Utc.ymd(262_143, 6, 1)),
+            ProjectBuilder::default()
+                .name("p2")
+                .probability(1.0)
+                .starts_after("p1", Duration::weeks(52)),
+        ]);
+        assert_eq!(names(&portfolio.sample(&mut rand::thread_rng())), ["p1"])
+    }
+
+    #[test]
+    fn successors_follow_their_predecessors() {
+        let portfolio = portfolio(vec![
+            ProjectBuilder::default()
+                .name("p2")
+                .probability(1.0)
+                .start_date(&Utc.ymd(2022, 1, 1))
+                .starts_after("p1", Duration::weeks(1)),
+            ProjectBuilder::default()
+                .name("p1")
+                .probability(1.0)
+                .start_date(&Utc.ymd(2022, 1, 1))
+                .duration_estimate(crate::estimate::Estimate::Uniform {
+                    min: 10.0,
+                    max: 40.0,
+                }),
+        ]);
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let outcome = portfolio.sample(&mut rng);
+            let (p1, p2) = (
+                outcome.project("p1").unwrap(),
+                outcome.project("p2").unwrap(),
+            );
+            assert_eq!(*p2.start_date(), *p1.end_date() + Duration::weeks(1));
+            assert_eq!(p2.duration(), Duration::weeks(4))
+        }
+    }
+
+    #[test]
+    fn waiting_for_the_team_avoids_overlaps() {
+        let project = |name| {
+            ProjectBuilder::default()
+                .name(name)
+                .probability(1.0)
+                .start_date(&Utc.ymd(2022, 1, 1))
+                .requires("rust", 1.0)
+                .starts_when_team_free()
+        };
+        let portfolio = portfolio(vec![
+            project("a"),
+            project("b"),
+            project("c").requires("design", 1.0),
+            ProjectBuilder::default()
+                .name("d")
+                .probability(1.0)
+                .start_date(&Utc.ymd(2022, 1, 1))
+                .requires("design", 1.0),
+        ]);
+        let outcome = portfolio.sample(&mut rand::thread_rng());
+        let starts: Vec<_> = outcome.projects().iter().map(|p| *p.start_date()).collect();
+        assert_eq!(
+            starts,
+            [
+                Utc.ymd(2022, 1, 1),
+                Utc.ymd(2022, 1, 29),
+                Utc.ymd(2022, 2, 26),
+                Utc.ymd(2022, 1, 1)
+            ]
+        )
+    }
+
+    #[test]
+    fn waiting_for_people_pushes_successors_back() {
+        let project = |name| {
+            ProjectBuilder::default()
+                .name(name)
+                .probability(1.0)
+                .start_date(&Utc.ymd(2022, 1, 1))
+                .requires("rust", 1.0)
+        };
+        let mut portfolio = portfolio(vec![
+            project("a"),
+            project("b"),
+            ProjectBuilder::default()
+                .name("c")
+                .probability(1.0)
+                .start_date(&Utc.ymd(2022, 1, 1))
+                .starts_after("b", Duration::zero()),
+        ]);
+        portfolio.set_team(Team {
+            members: vec![crate::expertise::Expert {
+                capacity: 1.0,
+                name: "Alice".into(),
+                skills: vec!["rust".into()],
+            }],
+        });
+        let result = crate::simulation::Simulation::new(&portfolio)
+            .start_date(&Utc.ymd(2022, 1, 1))
+            .end_date(&Utc.ymd(2022, 6, 1))
+            .trials(5)
+            .run()
+            .unwrap();
+        let delays: Vec<(&str, f64)> = result
+            .delays(&portfolio)
+            .iter()
+            .map(|(name, days)| (*name, days.max()))
+            .collect();
+        assert_eq!(delays, [("a", 0.0), ("b", 28.0), ("c", 56.0)])
+    }
+
+    #[test]
+    fn unknown_references_are_rejected() {
+        let mut portfolio = Portfolio::default();
+        let p = ProjectBuilder::default()
+            .name("p1")
+            .depends_on("laptop")
+            .build()
+            .unwrap();
+        portfolio.add_project(p).unwrap();
+        portfolio
+            .add_cost(CostBuilder::default().name("laptop").build().unwrap())
+            .unwrap();
+        assert_eq!(
+            portfolio.check_relationships(),
+            Err(PortfolioError::UnknownReference {
+                from: "p1".into(),
+                to: "laptop".into()
+            })
+        );
+        // Trials still run, without it.
+        assert!(portfolio
+            .sample(&mut rand::thread_rng())
+            .projects()
+            .is_empty())
+    }
+
+    #[test]
+    fn simulations_refuse_loops() {
+        let mut portfolio = Portfolio::default();
+        for (name, dependency) in [("a", "b"), ("b", "a")] {
+            let p = ProjectBuilder::default()
+                .name(name)
+                .depends_on(dependency)
+                .build()
+                .unwrap();
+            portfolio.add_project(p).unwrap();
+        }
+        assert_eq!(
+            crate::simulation::Simulation::new(&portfolio)
+                .trials(1)
+                .run(),
+            Err(SimulationError::Portfolio(PortfolioError::Cycle(vec![
+                "a".into(),
+                "b".into(),
+                "a".into()
+            ])))
+        )
+    }
+
+    #[test]
+    fn outcomes_are_in_the_reporting_currency() {
+        let mut rates = ExchangeRates::new(Currency::GBP);
+        rates.add_rate(Currency::EUR, 0.5).unwrap();
+        rates.add_rate(Currency::USD, 0.8).unwrap();
+        let mut portfolio = Portfolio::default();
+        portfolio.set_exchange_rates(rates).unwrap();
+        let p = ProjectBuilder::default()
+            .name("p1")
+            .probability(1.0)
+            .value(3_000_000_000)
+            .currency(Currency::EUR)
+            .build()
+            .unwrap();
+        let c = CostBuilder::default()
+            .name("c1")
+            .amount(1000)
+            .currency(Currency::USD)
+            .build()
+            .unwrap();
+        portfolio.add_project(p).unwrap();
+        portfolio.add_cost(c).unwrap();
+
+        let mut outcome = portfolio.sample(&mut rand::thread_rng());
+        let p1 = outcome.project("p1").unwrap();
+        assert_eq!(p1.value(), Money::new(1.5e9, Currency::GBP));
+        assert_eq!(outcome.cost("c1").unwrap().amount().units(), 800);
+        assert!(outcome.set_exchange_rates(ExchangeRates::default()).is_ok());
+        assert_eq!(
+            portfolio.set_exchange_rates(ExchangeRates::default()),
+            Err(PortfolioError::UnknownCurrency {
+                name: "p1".into(),
+                currency: Currency::EUR
+            })
+        )
+    }
+}
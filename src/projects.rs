@@ -1,8 +1,29 @@
-use crate::{allocation::Allocation, traits::Contribution};
+use crate::{
+    allocation::Allocation,
+    estimate::Estimate,
+    expertise::Requirement,
+    money::{Currency, ExchangeRates, Money, MoneyError},
+    recognition::Recognition,
+    traits::{Breakdown, Contribution, Sample, TimeBound, TimeBoundError},
+};
 use chrono::{prelude::*, Duration};
+use rand::Rng;
+
+/// The most days a Project can start late, or run for: a century.
+pub const MAX_DAYS: i64 = 36_525;
 
 #[derive(PartialEq, Debug)]
 pub enum ProjectBuilderError {
+    CorrelatedAlternative,
+    InvalidCorrelation,
+    InvalidDuration,
+    InvalidLag,
+    InvalidProbability,
+    InvalidRecognition,
+    InvalidRequirement,
+    InvalidStartDelay,
+    InvalidValue,
+    TooLarge,
     ZeroLengthDuration,
 }
 
@@ -10,26 +31,189 @@ impl std::error::Error for ProjectBuilderError {}
 impl std::fmt::Display for ProjectBuilderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self {
+            ProjectBuilderError::CorrelatedAlternative => {
+                write!(
+                    f,
+                    "Project can't be both one of a group of alternatives and correlated."
+                )
+            }
+            ProjectBuilderError::InvalidCorrelation => {
+                write!(f, "Project correlation must be between 0.0 and 1.0.")
+            }
+            ProjectBuilderError::InvalidDuration => {
+                write!(
+                    f,
+                    "Project duration must be a valid, non-negative estimate of at most {} days.",
+                    MAX_DAYS
+                )
+            }
+            ProjectBuilderError::InvalidLag => write!(
+                f,
+                "Project lag after a predecessor must be within {} days either way.",
+                Predecessor::MAX_LAG_DAYS
+            ),
+            ProjectBuilderError::InvalidProbability => {
+                write!(f, "Project probability must be between 0.0 and 1.0.")
+            }
+            ProjectBuilderError::InvalidRecognition => {
+                write!(f, "Project milestones must pay out exactly its value.")
+            }
+            ProjectBuilderError::InvalidRequirement => {
+                write!(f, "Project requirements must need more than 0 FTE.")
+            }
+            ProjectBuilderError::InvalidStartDelay => write!(
+                f,
+                "Project start delay must be a valid estimate within {} days either way.",
+                MAX_DAYS
+            ),
+            ProjectBuilderError::InvalidValue => {
+                write!(f, "Project value must be a valid, non-negative estimate.")
+            }
+            ProjectBuilderError::TooLarge => write!(f, "Project value is too large."),
             ProjectBuilderError::ZeroLengthDuration => write!(f, "Project has no duration."),
         }
     }
 }
 
+/// # Correlation
+/// Puts a Project in a group that tends to win, or lose, together,
+/// like deals with the same client or in the same market.
+///
+/// Each Project's chance still holds on its own. `strength` goes from
+/// 0.0, independent of the group, to 1.0, rising and falling with it;
+/// two Projects with strengths `a` and `b` are correlated by `√(a × b)`
+/// under a Gaussian copula.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Correlation {
+    pub group: String,
+    pub strength: f64,
+}
+
+/// # Predecessor
+/// A Project that has to end before another can start,
+/// with `lag_days` to wait in between.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Predecessor {
+    #[cfg_attr(feature = "json", serde(default))]
+    pub lag_days: i64,
+    pub project: String,
+}
+
+impl Predecessor {
+    /// The longest lag either way, in days.
+    pub const MAX_LAG_DAYS: i64 = MAX_DAYS;
+}
+
 /// # ProjectBuilder
 /// Constructs Projects.
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(from = "ProjectInput"))]
 pub struct ProjectBuilder {
     allocation: Allocation,
+    correlation: Option<Correlation>,
+    currency: Currency,
+    dependencies: Vec<String>,
+    duration: Estimate,
+    exclusions: Vec<String>,
+    group: Option<String>,
     name: String,
-    value: u32,
+    predecessors: Vec<Predecessor>,
+    probability: f64,
+    recognition: Recognition,
+    requirements: Vec<Requirement>,
+    start_delay: Estimate,
+    value: Estimate,
+    waits_for_team: bool,
 }
 
 impl Default for ProjectBuilder {
     fn default() -> Self {
+        let allocation = Allocation::default();
         ProjectBuilder {
-            allocation: Allocation::default(),
+            allocation,
+            correlation: None,
+            currency: Currency::default(),
+            dependencies: vec![],
+            duration: Estimate::Fixed(allocation.duration().num_days() as f64),
+            exclusions: vec![],
+            group: None,
             name: "New Project".into(),
-            value: 20000,
+            predecessors: vec![],
+            probability: 1.0,
+            recognition: Recognition::default(),
+            requirements: vec![],
+            start_delay: Estimate::Fixed(0.0),
+            value: Estimate::Fixed(20000.0),
+            waits_for_team: false,
+        }
+    }
+}
+
+/// The fields a Project is written with in JSON, all optional.
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectInput {
+    allocation: Option<Allocation>,
+    correlation: Option<Correlation>,
+    currency: Option<Currency>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    duration: Option<Estimate>,
+    #[serde(default)]
+    exclusions: Vec<String>,
+    group: Option<String>,
+    name: Option<String>,
+    #[serde(default)]
+    predecessors: Vec<Predecessor>,
+    probability: Option<f64>,
+    recognition: Option<Recognition>,
+    #[serde(default)]
+    requirements: Vec<Requirement>,
+    start_delay: Option<Estimate>,
+    value: Option<Estimate>,
+    #[serde(default)]
+    waits_for_team: bool,
+}
+
+#[cfg(feature = "json")]
+impl From<ProjectInput> for ProjectBuilder {
+    fn from(input: ProjectInput) -> Self {
+        let mut builder = ProjectBuilder::default();
+        if let Some(allocation) = input.allocation {
+            builder = builder
+                .start_date(&allocation.start_date)
+                .duration(&allocation.duration());
+        }
+        if let Some(duration) = input.duration {
+            builder = match input.allocation {
+                // Keep the dates as written, even if they've moved
+                // away from the estimate's expected value.
+                Some(_) => ProjectBuilder {
+                    duration,
+                    ..builder
+                },
+                None => builder.duration_estimate(duration),
+            };
+        }
+        ProjectBuilder {
+            correlation: input.correlation,
+            currency: input.currency.unwrap_or(builder.currency),
+            dependencies: input.dependencies,
+            exclusions: input.exclusions,
+            group: input.group,
+            name: input.name.unwrap_or(builder.name),
+            predecessors: input.predecessors,
+            probability: input.probability.unwrap_or(builder.probability),
+            recognition: input.recognition.unwrap_or(builder.recognition),
+            requirements: input.requirements,
+            start_delay: input.start_delay.unwrap_or(builder.start_delay),
+            value: input.value.unwrap_or(builder.value),
+            waits_for_team: input.waits_for_team,
+            ..builder
         }
     }
 }
@@ -46,33 +230,304 @@ impl ProjectBuilder {
     /// let date = Utc.ymd(2022, 8, 16);
     /// let project = ProjectBuilder::default()
     ///   .start_date(&date)
-    ///   .build();
+    ///   .build()
+    ///   .unwrap();
     /// assert_eq!(project.allocation().start_date(), &date)
     /// ```
     pub fn start_date(mut self, date: &Date<Utc>) -> ProjectBuilder {
         let duration = self.allocation.duration();
         self.allocation = Allocation {
             start_date: *date,
-            end_date: *date + duration,
+            // Past the last date there is, the project has no duration left.
+            end_date: date.checked_add_signed(duration).unwrap_or(*date),
         };
         self
     }
 
-    /// This method sets the project's value.
+    /// This method sets how many days the project might start
+    /// after its start date, up to [`MAX_DAYS`] either way.
     ///
     /// ## Example
     /// ```
+    /// use hallo::estimate::Estimate;
     /// use hallo::projects::ProjectBuilder;
+    /// use hallo::traits::TimeBound;
+    /// use chrono::{prelude::*, Duration};
+    ///
+    /// // Starts 2-6 weeks from now.
+    /// let today = Utc::today();
     /// let project = ProjectBuilder::default()
-    ///   .value(10000)
-    ///   .build();
-    /// assert_eq!(project.value(), 10000)
+    ///   .start_date(&today)
+    ///   .start_delay(Estimate::Uniform { min: 14.0, max: 42.0 })
+    ///   .build()
+    ///   .unwrap();
+    /// let allocation = project.sample_allocation(&mut rand::thread_rng());
+    /// assert!(*allocation.start_date() >= today + Duration::weeks(2));
+    /// assert!(*allocation.start_date() <= today + Duration::weeks(6))
+    /// ```
+    pub fn start_delay(mut self, days: Estimate) -> ProjectBuilder {
+        self.start_delay = days;
+        self
+    }
+
+    /// This method sets the project's value, in whole units of its currency.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::{ProjectBuilder, ProjectBuilderError};
+    /// let project = ProjectBuilder::default()
+    ///   .value(10_000_000_000)
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.value().units(), 10_000_000_000);
+    ///
+    /// let project = ProjectBuilder::default().value(u64::MAX).build();
+    /// assert_eq!(project, Err(ProjectBuilderError::TooLarge))
+    /// ```
+    pub fn value(mut self, value: u64) -> ProjectBuilder {
+        self.value = Estimate::Fixed(value as f64);
+        self
+    }
+
+    /// This method sets the currency the project's value is in.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, Money};
+    /// use hallo::projects::ProjectBuilder;
+    /// let project = ProjectBuilder::default()
+    ///   .value(5000)
+    ///   .currency(Currency::EUR)
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.value(), Money::new(5000.0, Currency::EUR))
+    /// ```
+    pub fn currency(mut self, currency: Currency) -> ProjectBuilder {
+        self.currency = currency;
+        self
+    }
+
+    /// This method sets the project's value as a range of possibilities.
+    /// The project's value becomes the estimate's expected value.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    /// use hallo::projects::ProjectBuilder;
+    /// let project = ProjectBuilder::default()
+    ///   .value_estimate(Estimate::Triangular { min: 8000.0, likely: 10000.0, max: 15000.0 })
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.value().units(), 11000)
     /// ```
-    pub fn value(mut self, value: u32) -> ProjectBuilder {
+    pub fn value_estimate(mut self, value: Estimate) -> ProjectBuilder {
         self.value = value;
         self
     }
 
+    /// This method sets the project's chance of happening,
+    /// from 0.0 (never) to 1.0 (certain). Projects are certain unless told otherwise.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::{ProjectBuilder, ProjectBuilderError};
+    /// let project = ProjectBuilder::default()
+    ///   .probability(0.8)
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.probability(), 0.8);
+    ///
+    /// let invalid = ProjectBuilder::default()
+    ///   .probability(1.5)
+    ///   .build();
+    /// assert_eq!(invalid, Err(ProjectBuilderError::InvalidProbability))
+    /// ```
+    pub fn probability(mut self, probability: f64) -> ProjectBuilder {
+        self.probability = probability;
+        self
+    }
+
+    /// This method sets when the project's value turns into money.
+    /// By default, it's spread evenly over every active day.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::recognition::Recognition;
+    /// use hallo::traits::Contribution;
+    ///
+    /// let project = ProjectBuilder::default()
+    ///   .value(5000)
+    ///   .start_date(&Utc.ymd(2022, 8, 1))
+    ///   .duration_weeks(2)
+    ///   .recognition(Recognition::End)
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.get_contribution_on(&Utc.ymd(2022, 8, 14)), 0);
+    /// assert_eq!(project.get_contribution_on(&Utc.ymd(2022, 8, 15)), 500000)
+    /// ```
+    pub fn recognition(mut self, recognition: Recognition) -> ProjectBuilder {
+        self.recognition = recognition;
+        self
+    }
+
+    /// This method adds a skill the project needs,
+    /// in full-time equivalents per week.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// let project = ProjectBuilder::default()
+    ///   .requires("rust", 1.5)
+    ///   .requires("design", 0.2)
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.requirements().len(), 2)
+    /// ```
+    pub fn requires(mut self, skill: &str, fte: f64) -> ProjectBuilder {
+        self.requirements.push(Requirement {
+            fte,
+            skill: String::from(skill),
+        });
+        self
+    }
+
+    /// This method makes the project depend on another one in the same Portfolio:
+    /// it only happens in trials where `project` happens too,
+    /// like a second phase that needs the first one won.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// let project = ProjectBuilder::default()
+    ///   .name("Phase 2")
+    ///   .depends_on("Phase 1")
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.dependencies(), ["Phase 1"])
+    /// ```
+    pub fn depends_on(mut self, project: &str) -> ProjectBuilder {
+        self.dependencies.push(String::from(project));
+        self
+    }
+
+    /// This method rules out the project in trials where `project` happens,
+    /// and the other way round. When both would happen, the one listed
+    /// first in the Portfolio does, unless it waits on a Project it depends on.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// let project = ProjectBuilder::default()
+    ///   .name("In-house build")
+    ///   .excludes("Agency build")
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.exclusions(), ["Agency build"])
+    /// ```
+    pub fn excludes(mut self, project: &str) -> ProjectBuilder {
+        self.exclusions.push(String::from(project));
+        self
+    }
+
+    /// This method puts the project in a group of alternatives,
+    /// like competing bids, exactly one of which happens in every trial.
+    /// Within a group, probabilities weigh how likely each one is to be picked,
+    /// out of the ones whose dependencies happened and that nothing rules out.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// let project = ProjectBuilder::default()
+    ///   .one_of("Tender")
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.group(), Some("Tender"))
+    /// ```
+    pub fn one_of(mut self, group: &str) -> ProjectBuilder {
+        self.group = Some(String::from(group));
+        self
+    }
+
+    /// This method holds the project back until `project` ends, plus `lag`,
+    /// in trials where both happen. A slipping predecessor pushes it back.
+    /// Its start date becomes the earliest it can start.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::Duration;
+    /// use hallo::projects::{ProjectBuilder, ProjectBuilderError};
+    /// let project = ProjectBuilder::default()
+    ///   .name("p2")
+    ///   .starts_after("p1", Duration::weeks(1))
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.predecessors()[0].lag_days, 7);
+    ///
+    /// let project = ProjectBuilder::default()
+    ///   .starts_after("p1", Duration::weeks(100_000))
+    ///   .build();
+    /// assert_eq!(project, Err(ProjectBuilderError::InvalidLag))
+    /// ```
+    pub fn starts_after(mut self, project: &str, lag: Duration) -> ProjectBuilder {
+        self.predecessors.push(Predecessor {
+            lag_days: lag.num_days(),
+            project: String::from(project),
+        });
+        self
+    }
+
+    /// This method holds the project back until the people it needs are free:
+    /// it won't overlap any Project listed before it that needs one of the same skills.
+    /// Its start date becomes the earliest it can start.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// let project = ProjectBuilder::default()
+    ///   .requires("rust", 1.0)
+    ///   .starts_when_team_free()
+    ///   .build()
+    ///   .unwrap();
+    /// assert!(project.waits_for_team())
+    /// ```
+    pub fn starts_when_team_free(mut self) -> ProjectBuilder {
+        self.waits_for_team = true;
+        self
+    }
+
+    /// This method correlates the project with the rest of `group`,
+    /// from 0.0, independent, to 1.0, as strongly as can be.
+    /// See [`Correlation`]. Alternatives, whose chances are weighed
+    /// against each other, can't be correlated too.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::{ProjectBuilder, ProjectBuilderError};
+    /// let project = ProjectBuilder::default()
+    ///   .correlated("Acme Corp", 0.6)
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.correlation().unwrap().group, "Acme Corp");
+    ///
+    /// let project = ProjectBuilder::default().correlated("Acme Corp", 1.5).build();
+    /// assert_eq!(project, Err(ProjectBuilderError::InvalidCorrelation));
+    ///
+    /// let project = ProjectBuilder::default()
+    ///   .correlated("Acme Corp", 0.6)
+    ///   .one_of("Tender")
+    ///   .build();
+    /// assert_eq!(project, Err(ProjectBuilderError::CorrelatedAlternative))
+    /// ```
+    pub fn correlated(mut self, group: &str, strength: f64) -> ProjectBuilder {
+        self.correlation = Some(Correlation {
+            group: String::from(group),
+            strength,
+        });
+        self
+    }
+
     /// This method sets the project's name.
     ///
     /// ## Example
@@ -80,7 +535,8 @@ impl ProjectBuilder {
     /// use hallo::projects::ProjectBuilder;
     /// let project = ProjectBuilder::default()
     ///   .name("My New Project".into())
-    ///   .build();
+    ///   .build()
+    ///   .unwrap();
     /// assert_eq!(project.name, "My New Project".to_string())
     /// ```
     pub fn name(mut self, name: &str) -> ProjectBuilder {
@@ -98,14 +554,21 @@ impl ProjectBuilder {
     /// let duration = Duration::weeks(33);
     /// let project = ProjectBuilder::default()
     ///   .duration_weeks(33)
-    ///   .build();
+    ///   .build()
+    ///   .unwrap();
     /// assert_eq!(project.duration(), duration)
     /// ```
     pub fn duration_weeks(self, num_of_weeks: i64) -> ProjectBuilder {
-        self.duration(&Duration::weeks(num_of_weeks))
+        match num_of_weeks.checked_mul(7) {
+            Some(days) if days.abs() <= MAX_DAYS => self.duration(&Duration::days(days)),
+            _ => ProjectBuilder {
+                duration: Estimate::Fixed(num_of_weeks as f64 * 7.0),
+                ..self
+            },
+        }
     }
 
-    /// This method sets the project's duration.
+    /// This method sets the project's duration, up to [`MAX_DAYS`].
     ///
     /// ## Example
     /// ```
@@ -115,47 +578,206 @@ impl ProjectBuilder {
     /// let duration = Duration::weeks(8);
     /// let project = ProjectBuilder::default()
     ///   .duration(&duration)
-    ///   .build();
+    ///   .build()
+    ///   .unwrap();
     /// assert_eq!(project.duration(), duration)
     /// ```
     pub fn duration(mut self, duration: &Duration) -> ProjectBuilder {
         let start_date = self.allocation.start_date;
         self.allocation = Allocation {
             start_date,
-            end_date: start_date + *duration,
+            end_date: start_date
+                .checked_add_signed(*duration)
+                .unwrap_or(start_date),
         };
+        self.duration = Estimate::Fixed(duration.num_days() as f64);
+        self
+    }
+
+    /// This method sets the project's duration, in days,
+    /// as a range of possibilities, up to [`MAX_DAYS`].
+    /// The project's duration becomes the estimate's expected value.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    /// use hallo::projects::ProjectBuilder;
+    /// use chrono::Duration;
+    ///
+    /// // Lasts 3-5 weeks, most likely 4.
+    /// let project = ProjectBuilder::default()
+    ///   .duration_estimate(Estimate::Triangular { min: 21.0, likely: 28.0, max: 35.0 })
+    ///   .build()
+    ///   .unwrap();
+    /// assert_eq!(project.duration(), Duration::weeks(4))
+    /// ```
+    pub fn duration_estimate(mut self, days: Estimate) -> ProjectBuilder {
+        let expected = days.expected().round();
+        if expected.abs() <= MAX_DAYS as f64 {
+            self = self.duration(&Duration::days(expected as i64));
+        }
+        self.duration = days;
         self
     }
 
     /// Builds the Project.
     /// Use at the end of the call chain.
-    pub fn build(self) -> Project {
-        Project {
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::{ProjectBuilder, ProjectBuilderError};
+    /// let project = ProjectBuilder::default()
+    ///   .duration_weeks(0)
+    ///   .build();
+    /// assert_eq!(project, Err(ProjectBuilderError::ZeroLengthDuration))
+    /// ```
+    pub fn build(self) -> Result<Project, ProjectBuilderError> {
+        if !self.duration.is_valid()
+            || self.duration.min() < 0.0
+            || largest(&self.duration) > MAX_DAYS as f64
+        {
+            return Err(ProjectBuilderError::InvalidDuration);
+        }
+        if self.allocation.duration() <= Duration::zero() {
+            return Err(ProjectBuilderError::ZeroLengthDuration);
+        }
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(ProjectBuilderError::InvalidProbability);
+        }
+        if !self.value.is_valid() || self.value.min() < 0.0 {
+            return Err(ProjectBuilderError::InvalidValue);
+        }
+        if Money::try_new(ceiling(&self.value), self.currency).is_err() {
+            return Err(ProjectBuilderError::TooLarge);
+        }
+        if !self.start_delay.is_valid()
+            || self.start_delay.min() < -MAX_DAYS as f64
+            || largest(&self.start_delay) > MAX_DAYS as f64
+        {
+            return Err(ProjectBuilderError::InvalidStartDelay);
+        }
+        if !self.recognition.is_valid() {
+            return Err(ProjectBuilderError::InvalidRecognition);
+        }
+        if let Some(correlation) = &self.correlation {
+            if !(0.0..=1.0).contains(&correlation.strength) {
+                return Err(ProjectBuilderError::InvalidCorrelation);
+            }
+            if self.group.is_some() {
+                return Err(ProjectBuilderError::CorrelatedAlternative);
+            }
+        }
+        if self
+            .predecessors
+            .iter()
+            .any(|p| p.lag_days.abs() > Predecessor::MAX_LAG_DAYS)
+        {
+            return Err(ProjectBuilderError::InvalidLag);
+        }
+        if self
+            .requirements
+            .iter()
+            .any(|r| !r.fte.is_finite() || r.fte <= 0.0)
+        {
+            return Err(ProjectBuilderError::InvalidRequirement);
+        }
+        Ok(Project {
             allocation: self.allocation,
             approx_value: self.value,
+            correlation: self.correlation,
+            currency: self.currency,
+            dependencies: self.dependencies,
+            duration: self.duration,
+            exclusions: self.exclusions,
+            group: self.group,
             name: self.name,
+            predecessors: self.predecessors,
+            probability: self.probability,
+            recognition: self.recognition,
+            requirements: self.requirements,
+            start_delay: self.start_delay,
+            waits_for_team: self.waits_for_team,
+        })
+    }
+}
+
+/// Returns the most an estimate can be.
+/// Log-normal estimates have no ceiling, so it's what they're expected to be.
+pub(crate) fn largest(value: &Estimate) -> f64 {
+    match value.max() {
+        max if max.is_finite() => max,
+        _ => value.expected(),
+    }
+}
+
+/// How far out, in standard deviations of its logarithm, a log-normal
+/// value is cut: about one draw in a million billion goes past it.
+const TAIL_SIGMAS: f64 = 8.0;
+
+/// Returns the most a value can be worth.
+/// Log-normal values have no ceiling, so draws are cut at their far tail.
+fn ceiling(value: &Estimate) -> f64 {
+    match *value {
+        Estimate::LogNormal { mean, std_dev } => {
+            let sigma = (1.0 + (std_dev / mean).powi(2)).ln().sqrt();
+            (mean.ln() - sigma * sigma / 2.0 + TAIL_SIGMAS * sigma).exp()
         }
+        _ => value.max(),
     }
 }
 
 /// # Project
 /// Represents a piece of work we might do in the future.
 /// Note: all values are designed to be approximate.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(try_from = "ProjectBuilder"))]
 pub struct Project {
     allocation: Allocation,
     pub name: String,
-    approx_value: u32,
+    #[cfg_attr(feature = "json", serde(rename = "value"))]
+    approx_value: Estimate,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    correlation: Option<Correlation>,
+    currency: Currency,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Vec::is_empty"))]
+    dependencies: Vec<String>,
+    duration: Estimate,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Vec::is_empty"))]
+    exclusions: Vec<String>,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    group: Option<String>,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Vec::is_empty"))]
+    predecessors: Vec<Predecessor>,
+    probability: f64,
+    recognition: Recognition,
+    requirements: Vec<Requirement>,
+    start_delay: Estimate,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "std::ops::Not::not"))]
+    waits_for_team: bool,
 }
 
 /// Returns
 /// Note: all values are designed to be approximate.
 impl Default for Project {
     fn default() -> Self {
+        let allocation = Allocation::default();
         Project {
-            allocation: Allocation::default(),
-            approx_value: 20000,
+            allocation,
+            approx_value: Estimate::Fixed(20000.0),
+            correlation: None,
+            currency: Currency::default(),
+            dependencies: vec![],
+            duration: Estimate::Fixed(allocation.duration().num_days() as f64),
+            exclusions: vec![],
+            group: None,
             name: "New Project".into(),
+            predecessors: vec![],
+            probability: 1.0,
+            recognition: Recognition::default(),
+            requirements: vec![],
+            start_delay: Estimate::Fixed(0.0),
+            waits_for_team: false,
         }
     }
 }
@@ -166,6 +788,15 @@ impl std::fmt::Display for Project {
     }
 }
 
+#[cfg(feature = "json")]
+impl TryFrom<ProjectBuilder> for Project {
+    type Error = ProjectBuilderError;
+
+    fn try_from(builder: ProjectBuilder) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
+
 impl Project {
     /// Returns the Project's approximate duration.
     ///
@@ -177,7 +808,7 @@ impl Project {
     /// let name = String::from("My Project");
     /// let start = Utc.ymd(2014, 7, 8);
     /// let duration = Duration::weeks(4);
-    /// let approx_value: u32 = 20000;
+    /// let approx_value: i64 = 20000;
     /// let p = Project::default();
     /// assert_eq!(p.duration(), duration)
     /// ```
@@ -195,15 +826,128 @@ impl Project {
     /// let name = String::from("My Project");
     /// let start = Utc.ymd(2014, 7, 8);
     /// let duration = Duration::weeks(2);
-    /// let approx_value: u32 = 20000;
+    /// let approx_value: i64 = 20000;
     /// let p = Project::default();
-    /// assert_eq!(p.value(), approx_value)
+    /// assert_eq!(p.value().units(), approx_value)
+    /// ```
+    pub fn value(&self) -> Money {
+        Money::new(self.approx_value.expected(), self.currency)
+    }
+
+    /// Returns the currency the Project's value is in.
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Returns the Project's value estimate.
+    ///
+    /// ## Example
     /// ```
-    pub fn value(&self) -> u32 {
+    /// use hallo::estimate::Estimate;
+    /// use hallo::projects::Project;
+    ///
+    /// let p = Project::default();
+    /// assert_eq!(p.value_estimate(), Estimate::Fixed(20000.0))
+    /// ```
+    pub fn value_estimate(&self) -> Estimate {
         self.approx_value
     }
 
-    /// Returns the Project's allocation.
+    /// Draws a possible value for the Project.
+    /// Log-normal values are cut at the far tail checked when the Project
+    /// was built. Fails if the value is still too large to keep.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    /// use hallo::projects::ProjectBuilder;
+    ///
+    /// let p = ProjectBuilder::default()
+    ///     .value_estimate(Estimate::Uniform { min: 1000.0, max: 2000.0 })
+    ///     .build()
+    ///     .unwrap();
+    /// let value = p.sample_value(&mut rand::thread_rng()).unwrap();
+    /// assert!((1000..=2000).contains(&value.units()))
+    /// ```
+    pub fn sample_value<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Money, MoneyError> {
+        Money::try_new(self.draw_value(rng), self.currency)
+    }
+
+    fn draw_value<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.approx_value
+            .sample(rng)
+            .min(ceiling(&self.approx_value))
+    }
+
+    /// Returns the Project's chance of happening.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::Project;
+    ///
+    /// let p = Project::default();
+    /// assert_eq!(p.probability(), 1.0)
+    /// ```
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    /// Returns when the Project's value turns into money.
+    pub fn recognition(&self) -> &Recognition {
+        &self.recognition
+    }
+
+    /// Returns the skills the Project needs.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::Project;
+    ///
+    /// let p = Project::default();
+    /// assert!(p.requirements().is_empty())
+    /// ```
+    pub fn requirements(&self) -> &[Requirement] {
+        &self.requirements
+    }
+
+    /// Returns the Projects that have to end before this one starts.
+    pub fn predecessors(&self) -> &[Predecessor] {
+        &self.predecessors
+    }
+
+    /// Checks if the Project waits for the people it needs to be free.
+    pub fn waits_for_team(&self) -> bool {
+        self.waits_for_team
+    }
+
+    /// Checks if the Project needs any of the same skills as `other`.
+    pub fn shares_skills_with(&self, other: &Project) -> bool {
+        self.requirements
+            .iter()
+            .any(|r| other.requirements.iter().any(|o| o.skill == r.skill))
+    }
+
+    /// Returns the group the Project wins or loses along with, if any.
+    pub fn correlation(&self) -> Option<&Correlation> {
+        self.correlation.as_ref()
+    }
+
+    /// Returns the Projects this one only happens alongside.
+    pub fn dependencies(&self) -> &[String] {
+        &self.dependencies
+    }
+
+    /// Returns the Projects this one never happens alongside.
+    pub fn exclusions(&self) -> &[String] {
+        &self.exclusions
+    }
+
+    /// Returns the group of alternatives the Project is one of, if any.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Returns the Project's planned allocation.
     ///
     /// ## Example
     /// ```
@@ -215,10 +959,92 @@ impl Project {
     pub fn allocation(&self) -> Allocation {
         self.allocation
     }
+
+    /// Keeps a fixed duration in step with the allocation
+    /// after its dates are moved.
+    fn sync_fixed_duration(&mut self) {
+        if let Estimate::Fixed(_) = self.duration {
+            self.duration = Estimate::Fixed(self.allocation.duration().num_days() as f64);
+        }
+    }
+
+    /// Moves the Project to start on `date`, keeping its duration.
+    pub(crate) fn move_to(&mut self, date: &Date<Utc>) {
+        let duration = self.allocation.duration();
+        self.allocation = Allocation {
+            start_date: *date,
+            end_date: *date + duration,
+        };
+    }
+
+    /// Returns the Project with its value converted to the reporting currency.
+    /// Fails when there's no rate for its currency,
+    /// or it could be worth too much once converted.
+    pub(crate) fn converted(&self, rates: &ExchangeRates) -> Result<Project, MoneyError> {
+        let approx_value = self.approx_value.scale(rates.rate(self.currency)?);
+        Money::try_new(ceiling(&approx_value), rates.reporting())?;
+        Ok(Project {
+            approx_value,
+            currency: rates.reporting(),
+            ..self.clone()
+        })
+    }
+
+    /// Draws what the Project is worth, to the hundredth,
+    /// and when it runs, once it's known to happen.
+    pub(crate) fn sample_happened<R: Rng + ?Sized>(&self, rng: &mut R) -> Project {
+        let allocation = self.sample_allocation(rng);
+        Project {
+            allocation,
+            approx_value: Estimate::Fixed((self.draw_value(rng) * 100.0).round() / 100.0),
+            duration: Estimate::Fixed(allocation.duration().num_days() as f64),
+            start_delay: Estimate::Fixed(0.0),
+            ..self.clone()
+        }
+    }
+
+    /// Draws a possible allocation for the Project,
+    /// allowing for a late start and a longer, or shorter, duration.
+    /// Every allocation lasts at least a day, and draws from long tails
+    /// are cut at [`MAX_DAYS`]. Allocations that would run past
+    /// the last date there is keep the planned dates.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::estimate::Estimate;
+    /// use hallo::projects::ProjectBuilder;
+    /// use chrono::Duration;
+    ///
+    /// let p = ProjectBuilder::default()
+    ///     .duration_estimate(Estimate::Uniform { min: 21.0, max: 35.0 })
+    ///     .build()
+    ///     .unwrap();
+    /// let allocation = p.sample_allocation(&mut rand::thread_rng());
+    /// assert!(allocation.duration() >= Duration::weeks(3));
+    /// assert!(allocation.duration() <= Duration::weeks(5))
+    /// ```
+    pub fn sample_allocation<R: Rng + ?Sized>(&self, rng: &mut R) -> Allocation {
+        let max = MAX_DAYS as f64;
+        let delay = self.start_delay.sample(rng).round().clamp(-max, max) as i64;
+        let duration = self.duration.sample(rng).round().clamp(1.0, max) as i64;
+        let start_date = self
+            .allocation
+            .start_date
+            .checked_add_signed(Duration::days(delay));
+        let end_date =
+            start_date.and_then(|date| date.checked_add_signed(Duration::days(duration)));
+        match start_date.zip(end_date) {
+            Some((start_date, end_date)) => Allocation {
+                start_date,
+                end_date,
+            },
+            None => self.allocation,
+        }
+    }
 }
 
 impl Contribution for Project {
-    /// Returns the contribution for a given month.
+    /// Returns the contribution for a given day.
     ///
     /// ### Example
     /// ```
@@ -229,15 +1055,75 @@ impl Contribution for Project {
     /// let name = String::from("My Project");
     /// let start = Utc.ymd(2014, 7, 8);
     /// let duration = Duration::weeks(2);
-    /// let approx_value: u32 = 20000;
+    /// let approx_value: i64 = 20000;
     /// let p = Project::default();
     /// p.get_contribution_on(&Utc.ymd(2014, 7, 8));
     /// ```    
-    fn get_contribution_on(&self, date: &Date<Utc>) -> u32 {
-        match self.allocation.is_active_on(date) {
-            true => self.approx_value,
-            false => 0_u32,
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        self.recognition
+            .amount_on(&self.allocation, self.value().minor(), date)
+    }
+
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        Ok(Some(self.currency))
+    }
+}
+
+impl TimeBound for Project {
+    fn start_date(&self) -> &Date<Utc> {
+        &self.allocation.start_date
+    }
+    fn end_date(&self) -> &Date<Utc> {
+        &self.allocation.end_date
+    }
+    fn set_start_date(&mut self, date: &Date<Utc>) -> Result<Date<Utc>, TimeBoundError> {
+        if *date >= self.allocation.end_date {
+            return Err(TimeBoundError::InvalidDatesError);
+        }
+        let start_date = self.allocation.set_start_date(date)?;
+        self.sync_fixed_duration();
+        Ok(start_date)
+    }
+    fn set_end_date(&mut self, date: &Date<Utc>) -> Result<Date<Utc>, TimeBoundError> {
+        if *date <= self.allocation.start_date {
+            return Err(TimeBoundError::InvalidDatesError);
         }
+        let end_date = self.allocation.set_end_date(date)?;
+        self.sync_fixed_duration();
+        Ok(end_date)
+    }
+}
+
+impl Breakdown for Project {
+    fn parts(&self) -> Vec<(&str, &dyn Contribution)> {
+        vec![(&self.name, self)]
+    }
+}
+
+impl Sample for Project {
+    type Outcome = Option<Project>;
+
+    /// Rolls the dice on whether the project happens,
+    /// using its probability, on what it's worth and on when it runs.
+    /// A project either happens in full or not at all.
+    ///
+    /// On its own, or in a `Vec`, a project is rolled for independently:
+    /// what it's correlated with, depends on, excludes or is an alternative to
+    /// only counts when it's sampled as part of a Portfolio.
+    ///
+    /// ### Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::traits::Sample;
+    ///
+    /// let p = ProjectBuilder::default().probability(1.0).build().unwrap();
+    /// assert_eq!(p.sample(&mut rand::thread_rng()), Some(p));
+    /// ```
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Outcome {
+        if !rng.gen_bool(self.probability) {
+            return None;
+        }
+        Some(self.sample_happened(rng))
     }
 }
 
@@ -254,7 +1140,7 @@ mod tests {
 
     #[test]
     fn default_builder_duration() {
-        let p = ProjectBuilder::default().build();
+        let p = ProjectBuilder::default().build().unwrap();
         assert_eq!(p.duration(), Duration::weeks(4))
     }
 
@@ -262,17 +1148,194 @@ mod tests {
     fn builder_set_date_duration() {
         let p = ProjectBuilder::default()
             .start_date(&Utc.ymd(2014, 7, 10))
-            .build();
+            .build()
+            .unwrap();
         assert_eq!(p.duration(), Duration::weeks(4))
     }
 
     #[test]
     fn dynamic_date() {
         let new_date = Utc.ymd(2014, 7, 10) + Duration::weeks(2);
-        let p = ProjectBuilder::default().start_date(&new_date).build();
+        let p = ProjectBuilder::default()
+            .start_date(&new_date)
+            .build()
+            .unwrap();
         assert_eq!(p.duration(), Duration::weeks(4))
     }
 
+    #[test]
+    fn builder_rejects_negative_probability() {
+        let p = ProjectBuilder::default().probability(-0.1).build();
+        assert_eq!(p, Err(ProjectBuilderError::InvalidProbability))
+    }
+
+    #[test]
+    fn builder_rejects_negative_value() {
+        let p = ProjectBuilder::default()
+            .value_estimate(Estimate::Uniform {
+                min: -10.0,
+                max: 10.0,
+            })
+            .build();
+        assert_eq!(p, Err(ProjectBuilderError::InvalidValue))
+    }
+
+    #[test]
+    fn builder_rejects_heavy_tails() {
+        let p = ProjectBuilder::default()
+            .value_estimate(Estimate::LogNormal {
+                mean: 1e16,
+                std_dev: 1e18,
+            })
+            .build();
+        assert_eq!(p, Err(ProjectBuilderError::TooLarge))
+    }
+
+    #[test]
+    fn sampled_values_are_cut_at_the_tail() {
+        let value = Estimate::LogNormal {
+            mean: 1e6,
+            std_dev: 1e8,
+        };
+        let p = ProjectBuilder::default()
+            .value_estimate(value)
+            .build()
+            .unwrap();
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let sampled = p.sample_value(&mut rng).unwrap();
+            assert!(sampled.amount() <= ceiling(&value))
+        }
+    }
+
+    #[test]
+    fn sampled_value_is_fixed() {
+        let p = ProjectBuilder::default()
+            .probability(1.0)
+            .value_estimate(Estimate::Pert {
+                min: 100.0,
+                likely: 200.0,
+                max: 400.0,
+            })
+            .build()
+            .unwrap();
+        let outcome = p.sample(&mut rand::thread_rng()).unwrap();
+        assert!(matches!(outcome.value_estimate(), Estimate::Fixed(_)));
+        assert!((100..=400).contains(&outcome.value().units()))
+    }
+
+    #[test]
+    fn builder_rejects_negative_duration() {
+        let p = ProjectBuilder::default()
+            .duration_estimate(Estimate::Uniform {
+                min: -7.0,
+                max: 21.0,
+            })
+            .build();
+        assert_eq!(p, Err(ProjectBuilderError::InvalidDuration))
+    }
+
+    #[test]
+    fn builder_rejects_endless_duration() {
+        let weeks = ProjectBuilder::default()
+            .duration_weeks(100_000_000)
+            .build();
+        assert_eq!(weeks, Err(ProjectBuilderError::InvalidDuration));
+        let days = ProjectBuilder::default()
+            .duration_estimate(Estimate::Fixed(1e15))
+            .build();
+        assert_eq!(days, Err(ProjectBuilderError::InvalidDuration))
+    }
+
+    #[test]
+    fn builder_rejects_endless_start_delay() {
+        let p = ProjectBuilder::default()
+            .start_delay(Estimate::Fixed(1e9))
+            .build();
+        assert_eq!(p, Err(ProjectBuilderError::InvalidStartDelay))
+    }
+
+    #[test]
+    fn long_tails_sample_within_bounds() {
+        let p = ProjectBuilder::default()
+            .start_delay(Estimate::LogNormal {
+                mean: 10.0,
+                std_dev: 1e6,
+            })
+            .build()
+            .unwrap();
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let allocation = p.sample_allocation(&mut rng);
+            assert!(allocation.duration() > Duration::zero());
+        }
+    }
+
+    #[test]
+    fn sampled_allocation_is_active() {
+        let start = Utc.ymd(2022, 8, 1);
+        let p = ProjectBuilder::default()
+            .probability(1.0)
+            .value(700)
+            .start_date(&start)
+            .start_delay(Estimate::Fixed(7.0))
+            .duration_weeks(1)
+            .build()
+            .unwrap();
+        let outcome = p.sample(&mut rand::thread_rng()).unwrap();
+        assert_eq!(outcome.get_contribution_on(&Utc.ymd(2022, 8, 5)), 0);
+        assert_eq!(outcome.get_contribution_on(&Utc.ymd(2022, 8, 12)), 10000)
+    }
+
+    #[test]
+    fn builder_rejects_zero_fte() {
+        let p = ProjectBuilder::default().requires("rust", 0.0).build();
+        assert_eq!(p, Err(ProjectBuilderError::InvalidRequirement))
+    }
+
+    #[test]
+    fn contributions_add_up_to_value() {
+        let p = ProjectBuilder::default()
+            .value(20000)
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .duration_weeks(4)
+            .build()
+            .unwrap();
+        let total: i64 = (0..60)
+            .map(|day| p.get_contribution_on(&(Utc.ymd(2022, 7, 20) + Duration::days(day))))
+            .sum();
+        assert_eq!(total, 2000000)
+    }
+
+    #[test]
+    fn contributions_keep_hundredths() {
+        let p = ProjectBuilder::default()
+            .value_estimate(Estimate::Fixed(100.07))
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .duration_weeks(1)
+            .build()
+            .unwrap();
+        let total: i64 = (0..10)
+            .map(|day| p.get_contribution_on(&(Utc.ymd(2022, 8, 1) + Duration::days(day))))
+            .sum();
+        assert_eq!(total, 10007)
+    }
+
+    #[test]
+    fn builder_rejects_unbalanced_milestones() {
+        let p = ProjectBuilder::default()
+            .recognition(Recognition::Milestones(vec![]))
+            .build();
+        assert_eq!(p, Err(ProjectBuilderError::InvalidRecognition))
+    }
+
+    #[test]
+    fn never_happens() {
+        let p = ProjectBuilder::default().probability(0.0).build().unwrap();
+        let mut rng = rand::thread_rng();
+        assert!((0..100).all(|_| p.sample(&mut rng).is_none()))
+    }
+
     // #[test]
     // fn contribution_in_past() {
     //     let name = String::from("My Project");
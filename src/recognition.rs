@@ -0,0 +1,205 @@
+use crate::allocation::Allocation;
+use chrono::prelude::*;
+
+/// # Milestone
+/// A payment due once a share of the work is done.
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Milestone {
+    /// How far through the allocation, from 0.0 (start) to 1.0 (end).
+    pub progress: f64,
+    /// Share of the project's value paid, from 0.0 to 1.0.
+    pub share: f64,
+}
+
+/// # Recognition
+/// When a project's value turns into money.
+///
+/// Every strategy pays out the project's whole value,
+/// spread over the allocation's active days.
+#[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
+pub enum Recognition {
+    /// Spread evenly over every active day.
+    #[default]
+    Daily,
+    /// Paid in full on the first active day.
+    Start,
+    /// Paid in full on the last active day.
+    End,
+    /// Paid in instalments as the work progresses.
+    Milestones(Vec<Milestone>),
+    /// Invoiced on the last active day of each calendar month,
+    /// for the days worked in it.
+    Monthly,
+}
+
+impl Recognition {
+    /// Checks the strategy pays out exactly the project's value.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::recognition::{Milestone, Recognition};
+    ///
+    /// let half = Milestone { progress: 0.5, share: 0.5 };
+    /// assert!(!Recognition::Milestones(vec![half]).is_valid());
+    /// assert!(Recognition::Milestones(vec![half, half]).is_valid())
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Recognition::Milestones(milestones) => {
+                let total: f64 = milestones.iter().map(|m| m.share).sum();
+                !milestones.is_empty()
+                    && milestones
+                        .iter()
+                        .all(|m| (0.0..=1.0).contains(&m.progress) && m.share > 0.0)
+                    && (total - 1.0).abs() < 1e-6
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns how much of `value` is recognised on a given date.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::allocation::Allocation;
+    /// use hallo::recognition::Recognition;
+    ///
+    /// let a = Allocation { start_date: Utc.ymd(2022, 8, 1), end_date: Utc.ymd(2022, 8, 4) };
+    /// let paid: Vec<_> = (2..=4)
+    ///     .map(|day| Recognition::Daily.amount_on(&a, 100, &Utc.ymd(2022, 8, day)))
+    ///     .collect();
+    /// assert_eq!(paid, vec![33, 33, 34])
+    /// ```
+    pub fn amount_on(&self, allocation: &Allocation, value: i64, date: &Date<Utc>) -> i64 {
+        if !allocation.is_active_on(date) {
+            return 0;
+        }
+        let days = allocation.duration().num_days();
+        let day = (*date - allocation.start_date).num_days();
+        let earned_by = |day: i64| cumulative(value, day as f64 / days as f64);
+        match self {
+            Recognition::Daily => earned_by(day) - earned_by(day - 1),
+            Recognition::Start if day == 1 => value,
+            Recognition::End if day == days => value,
+            Recognition::Start | Recognition::End => 0,
+            Recognition::Milestones(milestones) => {
+                let total: f64 = milestones.iter().map(|m| m.share).sum();
+                let mut sorted = milestones.clone();
+                sorted.sort_by(|a, b| a.progress.total_cmp(&b.progress));
+                let mut paid_before = 0.0;
+                let mut amount = 0;
+                for milestone in sorted {
+                    let due = ((milestone.progress * days as f64).ceil() as i64).clamp(1, days);
+                    let paid_after = paid_before + milestone.share / total;
+                    if due == day {
+                        amount += cumulative(value, paid_after) - cumulative(value, paid_before);
+                    }
+                    paid_before = paid_after;
+                }
+                amount
+            }
+            Recognition::Monthly => {
+                let is_month_end = date.succ().day() == 1;
+                if !is_month_end && *date != allocation.end_date {
+                    return 0;
+                }
+                let previous_month_end = date.with_day(1).unwrap().pred();
+                let invoiced_day = (previous_month_end - allocation.start_date)
+                    .num_days()
+                    .max(0);
+                earned_by(day) - earned_by(invoiced_day)
+            }
+        }
+    }
+}
+
+/// Returns the whole amount earned once `share` of `value` is paid,
+/// rounding down so instalments always add up to `value`.
+fn cumulative(value: i64, share: f64) -> i64 {
+    if share >= 1.0 {
+        return value;
+    }
+    (value as f64 * share).floor() as i64
+}
+
+impl std::fmt::Display for Recognition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recognition::Daily => write!(f, "daily"),
+            Recognition::Start => write!(f, "at start"),
+            Recognition::End => write!(f, "at end"),
+            Recognition::Milestones(milestones) => write!(f, "{} milestones", milestones.len()),
+            Recognition::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::Duration;
+
+    fn allocation() -> Allocation {
+        Allocation {
+            start_date: Utc.ymd(2022, 8, 20),
+            end_date: Utc.ymd(2022, 10, 9),
+        }
+    }
+
+    fn paid(recognition: &Recognition) -> Vec<i64> {
+        let a = allocation();
+        (0..=a.duration().num_days() + 1)
+            .map(|day| recognition.amount_on(&a, 20000, &(a.start_date + Duration::days(day))))
+            .collect()
+    }
+
+    #[test]
+    fn every_strategy_pays_the_value() {
+        let strategies = [
+            Recognition::Daily,
+            Recognition::Start,
+            Recognition::End,
+            Recognition::Monthly,
+            Recognition::Milestones(vec![
+                Milestone {
+                    progress: 0.0,
+                    share: 0.3,
+                },
+                Milestone {
+                    progress: 0.5,
+                    share: 0.3,
+                },
+                Milestone {
+                    progress: 1.0,
+                    share: 0.4,
+                },
+            ]),
+        ];
+        for recognition in strategies {
+            assert_eq!(paid(&recognition).iter().sum::<i64>(), 20000)
+        }
+    }
+
+    #[test]
+    fn monthly_invoices() {
+        let payments: Vec<_> = paid(&Recognition::Monthly)
+            .into_iter()
+            .filter(|amount| *amount != 0)
+            .collect();
+        // 11 days in August, 30 in September and 9 in October.
+        assert_eq!(payments, vec![4400, 12000, 3600])
+    }
+
+    #[test]
+    fn lump_sums() {
+        let start = paid(&Recognition::Start);
+        let end = paid(&Recognition::End);
+        assert_eq!(start[1], 20000);
+        assert_eq!(end[end.len() - 2], 20000)
+    }
+}
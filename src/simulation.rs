@@ -0,0 +1,449 @@
+use crate::{
+    money::MoneyError,
+    portfolio::PortfolioError,
+    timeline::{Granularity, Timeline},
+    traits::{Contribution, Sample},
+};
+use chrono::{prelude::*, Duration};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+#[derive(PartialEq, Debug)]
+pub enum SimulationError {
+    /// The amounts can't be added up in a single currency.
+    Money(MoneyError),
+    /// The Projects in a Portfolio refer to each other in a way that can't happen.
+    Portfolio(PortfolioError),
+}
+
+impl std::error::Error for SimulationError {}
+impl std::fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            SimulationError::Money(e) => write!(f, "{}", e),
+            SimulationError::Portfolio(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<MoneyError> for SimulationError {
+    fn from(e: MoneyError) -> Self {
+        SimulationError::Money(e)
+    }
+}
+
+impl From<PortfolioError> for SimulationError {
+    fn from(e: PortfolioError) -> Self {
+        SimulationError::Portfolio(e)
+    }
+}
+
+/// # Simulation
+/// Rolls the dice on a set of things many, many times.
+///
+/// Every trial samples what happens and records the resulting
+/// net cash flow for each day from `start_date` (inclusive)
+/// to `end_date` (exclusive).
+///
+/// Trials run in parallel across threads. Each trial rolls its own dice,
+/// seeded from the simulation's seed, so the same subject and seed
+/// always give the same result, however many threads are used.
+pub struct Simulation<'a, S: Sample + ?Sized> {
+    end_date: Date<Utc>,
+    seed: Option<u64>,
+    start_date: Date<Utc>,
+    subject: &'a S,
+    threads: Option<usize>,
+    trials: usize,
+}
+
+impl<'a, S: Sample + ?Sized> Simulation<'a, S> {
+    /// Creates a Simulation of 1000 trials covering the next 52 weeks.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    ///
+    /// let projects = vec![
+    ///     ProjectBuilder::default().name("p1").build().unwrap(),
+    ///     ProjectBuilder::default().name("p2").build().unwrap(),
+    /// ];
+    /// let result = Simulation::new(&projects).trials(10).run().unwrap();
+    /// assert_eq!(result.trials.len(), 10)
+    /// ```
+    pub fn new(subject: &'a S) -> Self {
+        let today = Utc::today();
+        Simulation {
+            end_date: today + Duration::weeks(52),
+            seed: None,
+            start_date: today,
+            subject,
+            threads: None,
+            trials: 1000,
+        }
+    }
+
+    /// Sets the number of trials to run.
+    pub fn trials(mut self, trials: usize) -> Self {
+        self.trials = trials;
+        self
+    }
+
+    /// Sets the seed used to roll the dice.
+    /// Without one, a random seed is picked and recorded in the result.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    ///
+    /// let projects = vec![ProjectBuilder::default().name("p1").build().unwrap()];
+    /// let first = Simulation::new(&projects).seed(42).run().unwrap();
+    /// let second = Simulation::new(&projects).seed(first.seed).run().unwrap();
+    /// assert_eq!(first, second)
+    /// ```
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the number of threads trials run on.
+    /// Without one, every CPU core is used.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    ///
+    /// let projects = vec![ProjectBuilder::default().name("p1").build().unwrap()];
+    /// let single = Simulation::new(&projects).seed(42).threads(1).run().unwrap();
+    /// let many = Simulation::new(&projects).seed(42).threads(4).run().unwrap();
+    /// assert_eq!(single, many)
+    /// ```
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Sets the first day of the simulated timeline.
+    pub fn start_date(mut self, date: &Date<Utc>) -> Self {
+        self.start_date = *date;
+        self
+    }
+
+    /// Sets the day after the last day of the simulated timeline.
+    pub fn end_date(mut self, date: &Date<Utc>) -> Self {
+        self.end_date = *date;
+        self
+    }
+
+    /// Runs every trial.
+    /// Fails when the subject is in more than one currency, as the amounts
+    /// can't be added up without exchange rates. Put them in a Portfolio to convert them.
+    /// Also fails when the subject can't be sampled, such as a Portfolio
+    /// whose Projects depend on each other in a loop.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, MoneyError};
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::{Simulation, SimulationError};
+    ///
+    /// let projects = vec![
+    ///     ProjectBuilder::default().name("p1").build().unwrap(),
+    ///     ProjectBuilder::default().name("p2").currency(Currency::EUR).build().unwrap(),
+    /// ];
+    /// assert_eq!(
+    ///     Simulation::new(&projects).run(),
+    ///     Err(SimulationError::Money(MoneyError::CurrencyMismatch(Currency::GBP, Currency::EUR)))
+    /// )
+    /// ```
+    pub fn run(&self) -> Result<SimulationResult<S::Outcome>, SimulationError>
+    where
+        S: Contribution + Sync,
+        S::Outcome: Send,
+    {
+        self.subject.currency()?;
+        self.subject.check()?;
+        let seed = self.seed.unwrap_or_else(|| thread_rng().gen());
+        let run_trials = || {
+            (0..self.trials)
+                .into_par_iter()
+                .map(|trial| self.run_trial(seed, trial))
+                .collect()
+        };
+        let pool = self
+            .threads
+            .map(|threads| rayon::ThreadPoolBuilder::new().num_threads(threads).build());
+        let trials = match pool {
+            Some(Ok(pool)) => pool.install(run_trials),
+            // Fall back to the shared pool when a dedicated one can't start.
+            Some(Err(_)) | None => run_trials(),
+        };
+
+        Ok(SimulationResult {
+            end_date: self.end_date,
+            seed,
+            start_date: self.start_date,
+            trials,
+        })
+    }
+}
+
+impl<'a, S: Sample + ?Sized> Simulation<'a, S> {
+    fn run_trial(&self, seed: u64, trial: usize) -> Trial<S::Outcome> {
+        let mut rng = trial_rng(seed, trial);
+        let outcome = self.subject.sample(&mut rng);
+        let days = (self.end_date - self.start_date).num_days().max(0);
+        let daily = (0..days)
+            .map(|day| {
+                let date = self.start_date + Duration::days(day);
+                outcome.get_contribution_on(&date)
+            })
+            .collect();
+        Trial { daily, outcome }
+    }
+}
+
+/// Returns the dice for a single trial.
+/// Every trial gets its own stream, so results don't depend
+/// on the order trials are run in.
+fn trial_rng(seed: u64, trial: usize) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(trial as u64);
+    rng
+}
+
+/// # Trial
+/// A single roll of the dice.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trial<O> {
+    /// Net cash flow for each day of the simulated timeline, in hundredths.
+    pub daily: Vec<i64>,
+    /// What happened in this trial.
+    pub outcome: O,
+}
+
+impl<O> Trial<O> {
+    /// Returns the net cash flow across the simulated timeline, in hundredths.
+    pub fn total(&self) -> i64 {
+        self.daily.iter().sum()
+    }
+}
+
+/// # SimulationResult
+/// Every trial from a Simulation run.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationResult<O> {
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
+    pub end_date: Date<Utc>,
+    /// Seed the dice were rolled with.
+    pub seed: u64,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
+    pub start_date: Date<Utc>,
+    pub trials: Vec<Trial<O>>,
+}
+
+impl<O> SimulationResult<O> {
+    /// Returns each day of the simulated timeline.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::Project;
+    /// use hallo::simulation::Simulation;
+    ///
+    /// let projects = vec![Project::default()];
+    /// let result = Simulation::new(&projects)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .end_date(&Utc.ymd(2022, 9, 1))
+    ///     .trials(1)
+    ///     .run().unwrap();
+    /// assert_eq!(result.dates().count(), 31)
+    /// ```
+    pub fn dates(&self) -> impl Iterator<Item = Date<Utc>> + '_ {
+        let days = (self.end_date - self.start_date).num_days().max(0);
+        (0..days).map(move |day| self.start_date + Duration::days(day))
+    }
+
+    /// Returns each trial's net cash flow, period by period.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::Project;
+    /// use hallo::simulation::Simulation;
+    /// use hallo::timeline::Granularity;
+    ///
+    /// let projects = vec![Project::default()];
+    /// let result = Simulation::new(&projects)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .end_date(&Utc.ymd(2023, 1, 1))
+    ///     .trials(3)
+    ///     .run().unwrap();
+    /// let timelines = result.timelines(Granularity::Month);
+    /// assert_eq!(timelines.len(), 3);
+    /// assert_eq!(timelines[0].buckets.len(), 5)
+    /// ```
+    pub fn timelines(&self, granularity: Granularity) -> Vec<Timeline> {
+        self.trials
+            .iter()
+            .map(|trial| Timeline::from_daily(&self.start_date, &trial.daily, granularity))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::projects::ProjectBuilder;
+
+    fn projects() -> Vec<crate::projects::Project> {
+        vec![
+            ProjectBuilder::default()
+                .name("p1")
+                .value(1000)
+                .duration_weeks(1)
+                .start_date(&Utc.ymd(2022, 8, 1))
+                .build()
+                .unwrap(),
+            ProjectBuilder::default()
+                .name("p2")
+                .value(10)
+                .duration_weeks(2)
+                .start_date(&Utc.ymd(2022, 8, 10))
+                .build()
+                .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn timeline_covers_range() {
+        let projects = projects();
+        let result = Simulation::new(&projects)
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .end_date(&Utc.ymd(2022, 8, 15))
+            .trials(5)
+            .run()
+            .unwrap();
+        assert_eq!(result.trials.len(), 5);
+        assert!(result.trials.iter().all(|t| t.daily.len() == 14))
+    }
+
+    #[test]
+    fn timeline_matches_outcome() {
+        let projects = projects();
+        let result = Simulation::new(&projects)
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .end_date(&Utc.ymd(2022, 9, 1))
+            .trials(50)
+            .run()
+            .unwrap();
+        for trial in result.trials {
+            let expected: i64 = trial
+                .outcome
+                .iter()
+                .flatten()
+                .map(|p| p.value().minor())
+                .sum();
+            assert_eq!(trial.total(), expected)
+        }
+    }
+
+    #[test]
+    fn net_of_costs() {
+        let projects = projects();
+        let costs = vec![crate::costs::CostBuilder::default()
+            .amount(500)
+            .start_date(&Utc.ymd(2022, 8, 2))
+            .build()
+            .unwrap()];
+        let result = Simulation::new(&(&projects, &costs))
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .end_date(&Utc.ymd(2022, 9, 1))
+            .trials(50)
+            .run()
+            .unwrap();
+        for trial in result.trials {
+            let revenue = trial.outcome.0.get_contribution_on(&Utc.ymd(2022, 8, 2));
+            assert_eq!(trial.daily[1], revenue - 50000)
+        }
+    }
+
+    #[test]
+    fn costs_in_another_currency_are_refused() {
+        let projects = projects();
+        let costs = vec![crate::costs::CostBuilder::default()
+            .currency(crate::money::Currency::USD)
+            .build()
+            .unwrap()];
+        assert_eq!(
+            Simulation::new(&(&projects, &costs)).trials(1).run(),
+            Err(SimulationError::Money(MoneyError::CurrencyMismatch(
+                crate::money::Currency::GBP,
+                crate::money::Currency::USD
+            )))
+        )
+    }
+
+    #[test]
+    fn seeded_runs_are_repeatable() {
+        let projects = projects();
+        let run = |seed| {
+            Simulation::new(&projects)
+                .start_date(&Utc.ymd(2022, 8, 1))
+                .end_date(&Utc.ymd(2022, 9, 1))
+                .trials(100)
+                .seed(seed)
+                .run()
+                .unwrap()
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8))
+    }
+
+    #[test]
+    fn unseeded_runs_record_their_seed() {
+        let projects = projects();
+        let first = Simulation::new(&projects).trials(100).run().unwrap();
+        let second = Simulation::new(&projects)
+            .trials(100)
+            .seed(first.seed)
+            .run()
+            .unwrap();
+        assert_eq!(first, second)
+    }
+
+    #[test]
+    fn thread_count_does_not_change_results() {
+        let projects = projects();
+        let run = |threads| {
+            Simulation::new(&projects)
+                .trials(200)
+                .seed(3)
+                .threads(threads)
+                .run()
+                .unwrap()
+        };
+        let single = run(1);
+        assert_eq!(single, run(2));
+        assert_eq!(single, run(8))
+    }
+
+    #[test]
+    fn empty_range() {
+        let projects = projects();
+        let result = Simulation::new(&projects)
+            .start_date(&Utc.ymd(2022, 8, 1))
+            .end_date(&Utc.ymd(2022, 7, 1))
+            .trials(1)
+            .run()
+            .unwrap();
+        assert_eq!(result.trials[0].total(), 0)
+    }
+}
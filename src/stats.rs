@@ -0,0 +1,461 @@
+use crate::{
+    portfolio::Portfolio,
+    simulation::SimulationResult,
+    timeline::{Granularity, Period},
+    traits::TimeBound,
+};
+use chrono::prelude::*;
+
+/// # Summary
+/// Describes how a set of outcomes is spread out.
+/// Empty summaries report zero everywhere.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Summary {
+    mean: f64,
+    sorted: Vec<f64>,
+    std_dev: f64,
+}
+
+impl Summary {
+    /// Summarises a set of outcomes.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::stats::Summary;
+    ///
+    /// let s = Summary::new(&[30, 10, 20, 40]);
+    /// assert_eq!(s.mean(), 25.0);
+    /// assert_eq!(s.min(), 10.0);
+    /// assert_eq!(s.max(), 40.0);
+    /// assert_eq!(s.p50(), 25.0)
+    /// ```
+    pub fn new(values: &[i64]) -> Summary {
+        Summary::of(values.iter().map(|v| *v as f64).collect())
+    }
+
+    /// Summarises amounts of money given in hundredths,
+    /// reporting them in whole units, and fractions of them.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::stats::Summary;
+    ///
+    /// let s = Summary::from_minor(&[1050, 2099]);
+    /// assert_eq!(s.min(), 10.5);
+    /// assert_eq!(s.max(), 20.99)
+    /// ```
+    pub fn from_minor(values: &[i64]) -> Summary {
+        Summary::of(values.iter().map(|v| *v as f64 / 100.0).collect())
+    }
+
+    fn of(mut sorted: Vec<f64>) -> Summary {
+        sorted.sort_unstable_by(f64::total_cmp);
+        let count = sorted.len() as f64;
+        let (mean, std_dev) = match sorted.len() {
+            0 => (0.0, 0.0),
+            _ => {
+                let mean = sorted.iter().sum::<f64>() / count;
+                let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+                (mean, variance.sqrt())
+            }
+        };
+        Summary {
+            mean,
+            sorted,
+            std_dev,
+        }
+    }
+
+    /// Returns the number of outcomes.
+    pub fn count(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Returns the average outcome.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the (population) standard deviation.
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Returns the worst outcome.
+    pub fn min(&self) -> f64 {
+        self.sorted.first().copied().unwrap_or_default()
+    }
+
+    /// Returns the best outcome.
+    pub fn max(&self) -> f64 {
+        self.sorted.last().copied().unwrap_or_default()
+    }
+
+    /// Returns the value below which `percent`% of outcomes fall,
+    /// interpolating between outcomes.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::stats::Summary;
+    ///
+    /// let s = Summary::new(&(0..=100).collect::<Vec<_>>());
+    /// assert_eq!(s.percentile(10.0), 10.0);
+    /// assert_eq!(s.percentile(95.0), 95.0)
+    /// ```
+    pub fn percentile(&self, percent: f64) -> f64 {
+        if self.sorted.is_empty() {
+            return 0.0;
+        }
+        let rank = percent.clamp(0.0, 100.0) / 100.0 * (self.sorted.len() - 1) as f64;
+        let below = self.sorted[rank.floor() as usize];
+        let above = self.sorted[rank.ceil() as usize];
+        below + (above - below) * rank.fract()
+    }
+
+    pub fn p10(&self) -> f64 {
+        self.percentile(10.0)
+    }
+
+    pub fn p50(&self) -> f64 {
+        self.percentile(50.0)
+    }
+
+    pub fn p90(&self) -> f64 {
+        self.percentile(90.0)
+    }
+
+    /// Returns the share of outcomes above `target`, from 0.0 to 1.0.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::stats::Summary;
+    ///
+    /// let s = Summary::new(&[0, 0, 100, 200]);
+    /// assert_eq!(s.probability_of_exceeding(50.0), 0.5)
+    /// ```
+    pub fn probability_of_exceeding(&self, target: f64) -> f64 {
+        if self.sorted.is_empty() {
+            return 0.0;
+        }
+        let at_or_below = self.sorted.partition_point(|v| *v <= target);
+        (self.sorted.len() - at_or_below) as f64 / self.sorted.len() as f64
+    }
+
+    /// Returns the amount we reach or beat with a given probability.
+    /// "There's an 80% chance we earn at least `at_least(0.8)`."
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::stats::Summary;
+    ///
+    /// let s = Summary::new(&(0..=100).collect::<Vec<_>>());
+    /// assert_eq!(s.at_least(0.8), 20.0)
+    /// ```
+    pub fn at_least(&self, probability: f64) -> f64 {
+        self.percentile(100.0 - probability * 100.0)
+    }
+}
+
+/// # Bin
+/// How many outcomes fall from `from` (inclusive) to `until` (exclusive).
+/// The last bin of a histogram includes `until` too.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Bin {
+    pub count: usize,
+    pub from: f64,
+    pub until: f64,
+}
+
+impl Summary {
+    /// Splits the outcomes into equally wide bins, from the worst to the best.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::stats::Summary;
+    ///
+    /// let s = Summary::new(&[0, 1, 2, 3, 10]);
+    /// let bins = s.histogram(2);
+    /// assert_eq!((bins[0].from, bins[0].until, bins[0].count), (0.0, 5.0, 4));
+    /// assert_eq!((bins[1].from, bins[1].until, bins[1].count), (5.0, 10.0, 1))
+    /// ```
+    pub fn histogram(&self, bins: usize) -> Vec<Bin> {
+        if self.sorted.is_empty() || bins == 0 {
+            return vec![];
+        }
+        let (min, max) = (self.min(), self.max());
+        if min == max {
+            return vec![Bin {
+                count: self.count(),
+                from: min,
+                until: max,
+            }];
+        }
+        let width = (max - min) / bins as f64;
+        let mut histogram: Vec<Bin> = (0..bins)
+            .map(|index| Bin {
+                count: 0,
+                from: min + width * index as f64,
+                until: min + width * (index + 1) as f64,
+            })
+            .collect();
+        for value in &self.sorted {
+            let index = ((value - min) / width) as usize;
+            histogram[index.min(bins - 1)].count += 1;
+        }
+        histogram
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "P10 {:.0} | P50 {:.0} | P90 {:.0} | mean {:.0} ± {:.0} | min {:.0} | max {:.0}",
+            self.p10(),
+            self.p50(),
+            self.p90(),
+            self.mean(),
+            self.std_dev(),
+            self.min(),
+            self.max()
+        )
+    }
+}
+
+/// Written as the headline figures rather than every outcome.
+#[cfg(feature = "json")]
+impl serde::Serialize for Summary {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut summary = serializer.serialize_struct("Summary", 8)?;
+        summary.serialize_field("count", &self.count())?;
+        summary.serialize_field("mean", &self.mean())?;
+        summary.serialize_field("std_dev", &self.std_dev())?;
+        summary.serialize_field("min", &self.min())?;
+        summary.serialize_field("max", &self.max())?;
+        summary.serialize_field("p10", &self.p10())?;
+        summary.serialize_field("p50", &self.p50())?;
+        summary.serialize_field("p90", &self.p90())?;
+        summary.end()
+    }
+}
+
+/// The chance a standard normal draw is at most `x`.
+/// Accurate to about 1e-7, which is plenty for rolling dice.
+///
+/// ## Example
+/// ```
+/// use hallo::stats::normal_cdf;
+///
+/// assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+/// assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+/// assert!((normal_cdf(-1.96) - 0.025).abs() < 1e-4)
+/// ```
+pub fn normal_cdf(x: f64) -> f64 {
+    // Abramowitz and Stegun, formula 7.1.26, for erf(|x| / √2).
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * z);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// # Band
+/// The spread of outcomes on a given day.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Band {
+    pub date: Date<Utc>,
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+impl<O> SimulationResult<O> {
+    /// Summarises the net cash flow of every trial, in whole units.
+    ///
+    /// ## Example
+    /// ```
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    ///
+    /// let projects = vec![ProjectBuilder::default().probability(1.0).value(1000).build().unwrap()];
+    /// let result = Simulation::new(&projects).trials(100).run().unwrap();
+    /// assert_eq!(result.summary().p10(), 1000.0)
+    /// ```
+    pub fn summary(&self) -> Summary {
+        let totals: Vec<i64> = self.trials.iter().map(|trial| trial.total()).collect();
+        Summary::from_minor(&totals)
+    }
+
+    /// Returns the spread of the running total of net cash flow,
+    /// at the end of each day, in whole units.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    ///
+    /// let projects = vec![ProjectBuilder::default()
+    ///     .probability(1.0)
+    ///     .value(1000)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .build()
+    ///     .unwrap()];
+    /// let result = Simulation::new(&projects)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .end_date(&Utc.ymd(2022, 10, 1))
+    ///     .trials(10)
+    ///     .run().unwrap();
+    /// let fan = result.fan();
+    /// assert_eq!(fan.len(), 61);
+    /// assert_eq!(fan[0].p50, 0.0);
+    /// assert_eq!(fan[60].p50, 1000.0)
+    /// ```
+    pub fn fan(&self) -> Vec<Band> {
+        let running_totals: Vec<Vec<i64>> = self
+            .trials
+            .iter()
+            .map(|trial| {
+                trial
+                    .daily
+                    .iter()
+                    .scan(0, |total, value| {
+                        *total += value;
+                        Some(*total)
+                    })
+                    .collect()
+            })
+            .collect();
+        self.dates()
+            .enumerate()
+            .map(|(day, date)| {
+                let totals: Vec<i64> = running_totals.iter().map(|totals| totals[day]).collect();
+                let summary = Summary::from_minor(&totals);
+                Band {
+                    date,
+                    p10: summary.p10(),
+                    p50: summary.p50(),
+                    p90: summary.p90(),
+                }
+            })
+            .collect()
+    }
+
+    /// Summarises the net cash flow of every trial, period by period, in whole units.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    /// use hallo::timeline::Granularity;
+    ///
+    /// let projects = vec![ProjectBuilder::default()
+    ///     .probability(0.9)
+    ///     .start_date(&Utc.ymd(2022, 7, 10))
+    ///     .build()
+    ///     .unwrap()];
+    /// let result = Simulation::new(&projects)
+    ///     .start_date(&Utc.ymd(2022, 1, 1))
+    ///     .end_date(&Utc.ymd(2023, 1, 1))
+    ///     .trials(1000)
+    ///     .run().unwrap();
+    /// let (q3, summary) = &result.period_summaries(Granularity::Quarter)[2];
+    /// assert_eq!(q3.start_date, Utc.ymd(2022, 7, 1));
+    /// // There's (at least) an 80% chance we earn it all in Q3.
+    /// assert_eq!(summary.at_least(0.8), 20000.0)
+    /// ```
+    pub fn period_summaries(&self, granularity: Granularity) -> Vec<(Period, Summary)> {
+        let timelines = self.timelines(granularity);
+        let periods = granularity.periods(&self.start_date, &self.end_date);
+        periods
+            .into_iter()
+            .enumerate()
+            .map(|(index, period)| {
+                let values: Vec<i64> = timelines
+                    .iter()
+                    .map(|timeline| timeline.buckets[index].value)
+                    .collect();
+                (period, Summary::from_minor(&values))
+            })
+            .collect()
+    }
+}
+
+impl SimulationResult<Portfolio> {
+    /// Summarises how many days late every Project in the `plan` started,
+    /// over the trials it happened in, after slipping, waiting for
+    /// other Projects and waiting for the Team.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::estimate::Estimate;
+    /// use hallo::portfolio::Portfolio;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::simulation::Simulation;
+    ///
+    /// let mut portfolio = Portfolio::default();
+    /// let p = ProjectBuilder::default()
+    ///     .name("p1")
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .start_delay(Estimate::Uniform { min: 0.0, max: 10.0 })
+    ///     .build()
+    ///     .unwrap();
+    /// portfolio.add_project(p).unwrap();
+    /// let result = Simulation::new(&portfolio).trials(100).run().unwrap();
+    /// let delays = result.delays(&portfolio);
+    /// assert_eq!(delays[0].0, "p1");
+    /// assert!(delays[0].1.min() >= 0.0 && delays[0].1.max() <= 10.0)
+    /// ```
+    pub fn delays<'p>(&self, plan: &'p Portfolio) -> Vec<(&'p str, Summary)> {
+        plan.projects()
+            .iter()
+            .map(|planned| {
+                let days: Vec<i64> = self
+                    .trials
+                    .iter()
+                    .filter_map(|trial| trial.outcome.project(&planned.name))
+                    .map(|outcome| (*outcome.start_date() - *planned.start_date()).num_days())
+                    .collect();
+                (planned.name.as_str(), Summary::new(&days))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let s = Summary::new(&[]);
+        assert_eq!(s.count(), 0);
+        assert_eq!(s.p90(), 0.0);
+        assert_eq!(s.probability_of_exceeding(0.0), 0.0)
+    }
+
+    #[test]
+    fn std_dev() {
+        let s = Summary::new(&[2, 4, 4, 4, 5, 5, 7, 9]);
+        assert_eq!(s.mean(), 5.0);
+        assert_eq!(s.std_dev(), 2.0)
+    }
+
+    #[test]
+    fn interpolated_percentile() {
+        let s = Summary::new(&[10, 20]);
+        assert_eq!(s.percentile(25.0), 12.5);
+        assert_eq!(s.percentile(150.0), 20.0)
+    }
+}
@@ -0,0 +1,376 @@
+//! Charts as standalone SVG documents, for reports and wiki pages.
+
+use crate::{
+    projects::Project,
+    stats::{Band, Bin},
+    terminal::compact,
+    traits::TimeBound,
+};
+use chrono::prelude::*;
+use std::fmt::Write;
+
+const BAND: &str = "#9ecae1";
+const LINE: &str = "#08519c";
+const MUTED: &str = "#969696";
+const TODAY: &str = "#de2d26";
+const FONT: &str = "font-family=\"sans-serif\" font-size=\"12\"";
+const ROW_HEIGHT: f64 = 24.0;
+
+/// The area inside the axes, in pixels.
+struct Plot {
+    bottom: f64,
+    left: f64,
+    right: f64,
+    top: f64,
+}
+
+impl Plot {
+    fn new(width: u32, height: u32, left: f64) -> Self {
+        Plot {
+            bottom: height as f64 - 40.0,
+            left,
+            right: width as f64 - 20.0,
+            top: 40.0,
+        }
+    }
+
+    /// Maps `value`, somewhere from `low` to `high`, across the plot.
+    fn x(&self, value: f64, low: f64, high: f64) -> f64 {
+        self.left + (value - low) / span(low, high) * (self.right - self.left)
+    }
+
+    /// Maps `value`, somewhere from `low` to `high`, up the plot.
+    fn y(&self, value: f64, low: f64, high: f64) -> f64 {
+        self.bottom - (value - low) / span(low, high) * (self.bottom - self.top)
+    }
+}
+
+fn span(low: f64, high: f64) -> f64 {
+    if high > low {
+        high - low
+    } else {
+        1.0
+    }
+}
+
+/// Draws a fan chart of the running total over time,
+/// with the P10 to P90 band shaded and the median as a line.
+///
+/// ## Example
+/// ```
+/// use chrono::prelude::*;
+/// use hallo::projects::ProjectBuilder;
+/// use hallo::simulation::Simulation;
+/// use hallo::svg::fan_chart;
+///
+/// let projects = vec![ProjectBuilder::default().start_date(&Utc.ymd(2022, 8, 1)).build().unwrap()];
+/// let result = Simulation::new(&projects)
+///     .start_date(&Utc.ymd(2022, 8, 1))
+///     .end_date(&Utc.ymd(2022, 10, 1))
+///     .run().unwrap();
+/// let svg = fan_chart(&result.fan(), 800, 400);
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains("<polygon"))
+/// ```
+pub fn fan_chart(bands: &[Band], width: u32, height: u32) -> String {
+    let mut svg = open(width, height, "Running total, P10 to P90");
+    if let (Some(first), Some(last)) = (bands.first(), bands.last()) {
+        let plot = Plot::new(width, height, 60.0);
+        let low = bands.iter().map(|b| b.p10).fold(0.0, f64::min);
+        let high = bands.iter().map(|b| b.p90).fold(0.0, f64::max);
+        let days = (bands.len() - 1) as f64;
+        let point = |day: usize, value: f64| {
+            format!(
+                "{:.1},{:.1}",
+                plot.x(day as f64, 0.0, days),
+                plot.y(value, low, high)
+            )
+        };
+
+        let mut outline: Vec<String> = bands
+            .iter()
+            .enumerate()
+            .map(|(d, b)| point(d, b.p90))
+            .collect();
+        outline.extend(bands.iter().enumerate().rev().map(|(d, b)| point(d, b.p10)));
+        let median: Vec<String> = bands
+            .iter()
+            .enumerate()
+            .map(|(d, b)| point(d, b.p50))
+            .collect();
+        let zero = plot.y(0.0, low, high);
+
+        let _ = writeln!(
+            svg,
+            "<polygon points=\"{}\" fill=\"{}\"/>",
+            outline.join(" "),
+            BAND
+        );
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-dasharray=\"4\"/>",
+            plot.left, zero, plot.right, zero, MUTED
+        );
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            median.join(" "),
+            LINE
+        );
+        for (value, y) in [(high, plot.top), (0.0, zero), (low, plot.bottom)] {
+            y_label(&mut svg, &plot, y, &compact(value));
+        }
+        x_labels(&mut svg, &plot, &first.date, &last.date);
+    }
+    svg + "</svg>\n"
+}
+
+/// Draws a bar for every Project, from the start to the end of its Allocation.
+/// Bars are shaded by the Project's chance of happening
+/// and labelled with its value and currency, and `today` is marked when it's in range.
+///
+/// ## Example
+/// ```
+/// use chrono::prelude::*;
+/// use hallo::projects::ProjectBuilder;
+/// use hallo::svg::gantt;
+///
+/// let projects = vec![
+///     ProjectBuilder::default().name("p1").probability(0.5).start_date(&Utc.ymd(2022, 8, 1)).build().unwrap(),
+///     ProjectBuilder::default().name("p2").start_date(&Utc.ymd(2022, 9, 1)).build().unwrap(),
+/// ];
+/// let svg = gantt(&projects, &Utc.ymd(2022, 8, 15), 800);
+/// assert_eq!(svg.matches("<rect").count(), 3);
+/// assert!(svg.contains(">p2</text>"));
+/// assert!(svg.contains(">20k GBP, 50%</text>"));
+/// assert!(svg.contains(">today</text>"))
+/// ```
+pub fn gantt(projects: &[Project], today: &Date<Utc>, width: u32) -> String {
+    let height = (80.0 + ROW_HEIGHT * projects.len() as f64) as u32;
+    let mut svg = open(width, height, "Projects");
+    let first = projects.iter().map(|p| *p.start_date()).min();
+    let last = projects.iter().map(|p| *p.end_date()).max();
+    if let (Some(first), Some(last)) = (first, last) {
+        let longest_name = projects.iter().map(|p| p.name.chars().count()).max();
+        let mut plot = Plot::new(width, height, 20.0 + 7.0 * longest_name.unwrap_or(0) as f64);
+        plot.right -= 110.0;
+        let days = (last - first).num_days() as f64;
+        let x = |date: &Date<Utc>| plot.x((*date - first).num_days() as f64, 0.0, days);
+
+        for (row, project) in projects.iter().enumerate() {
+            let top = plot.top + ROW_HEIGHT * row as f64;
+            let middle = top + ROW_HEIGHT / 2.0 + 4.0;
+            let (start, end) = (x(project.start_date()), x(project.end_date()));
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" {}>{}</text>",
+                plot.left - 8.0,
+                middle,
+                FONT,
+                escape(&project.name)
+            );
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" fill-opacity=\"{:.2}\" stroke=\"{}\"><title>{}</title></rect>",
+                start,
+                top + 4.0,
+                (end - start).max(1.0),
+                ROW_HEIGHT - 8.0,
+                LINE,
+                project.probability().max(0.1),
+                LINE,
+                escape(&project.to_string())
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" {}>{} {}, {:.0}%</text>",
+                end.max(start + 1.0) + 6.0,
+                middle,
+                FONT,
+                compact(project.value().amount()),
+                project.currency(),
+                project.probability() * 100.0
+            );
+        }
+        if (first..=last).contains(today) {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x:.1}\" y1=\"{:.1}\" x2=\"{x:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-dasharray=\"4\"/>\n\
+                 <text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\" fill=\"{}\" {}>today</text>",
+                plot.top,
+                plot.bottom,
+                TODAY,
+                plot.top - 6.0,
+                TODAY,
+                FONT,
+                x = x(today)
+            );
+        }
+        x_labels(&mut svg, &plot, &first, &last);
+    }
+    svg + "</svg>\n"
+}
+
+/// Wraps SVG charts in a page of their own, to open in a browser.
+///
+/// ## Example
+/// ```
+/// use chrono::prelude::*;
+/// use hallo::projects::Project;
+/// use hallo::svg::{gantt, html};
+///
+/// let page = html("Pipeline", &[gantt(&[Project::default()], &Utc::today(), 800)]);
+/// assert!(page.starts_with("<!DOCTYPE html>"));
+/// assert!(page.contains("<title>Pipeline</title>"))
+/// ```
+pub fn html(title: &str, charts: &[String]) -> String {
+    let mut page = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n",
+        escape(title)
+    );
+    for chart in charts {
+        page += chart;
+    }
+    page + "</body>\n</html>\n"
+}
+
+/// Draws a histogram of outcomes, one bar per bin.
+///
+/// ## Example
+/// ```
+/// use hallo::stats::Summary;
+/// use hallo::svg::histogram;
+///
+/// let svg = histogram(&Summary::new(&[0, 0, 0, 10]).histogram(2), 800, 400);
+/// assert_eq!(svg.matches("<rect").count(), 1 + 2)
+/// ```
+pub fn histogram(bins: &[Bin], width: u32, height: u32) -> String {
+    let mut svg = open(width, height, "Outcomes");
+    if let (Some(first), Some(last)) = (bins.first(), bins.last()) {
+        let plot = Plot::new(width, height, 60.0);
+        let most = bins.iter().map(|bin| bin.count).max().unwrap_or(0) as f64;
+        let bar_width = (plot.right - plot.left) / bins.len() as f64;
+
+        for (index, bin) in bins.iter().enumerate() {
+            let top = plot.y(bin.count as f64, 0.0, most);
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" stroke=\"white\"><title>{} to {}: {}</title></rect>",
+                plot.left + bar_width * index as f64,
+                top,
+                bar_width,
+                plot.bottom - top,
+                LINE,
+                compact(bin.from),
+                compact(bin.until),
+                bin.count
+            );
+        }
+        for (value, y) in [(most, plot.top), (0.0, plot.bottom)] {
+            y_label(&mut svg, &plot, y, &value.to_string());
+        }
+        for (value, x, anchor) in [
+            (first.from, plot.left, "start"),
+            (last.until, plot.right, "end"),
+        ] {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\" {}>{}</text>",
+                x,
+                plot.bottom + 20.0,
+                anchor,
+                FONT,
+                compact(value)
+            );
+        }
+    }
+    svg + "</svg>\n"
+}
+
+/// Starts a document with a white background and a title.
+fn open(width: u32, height: u32, title: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n\
+         <text x=\"20\" y=\"24\" font-family=\"sans-serif\" font-size=\"16\">{t}</text>\n",
+        w = width,
+        h = height,
+        t = escape(title)
+    )
+}
+
+fn y_label(svg: &mut String, plot: &Plot, y: f64, text: &str) {
+    let _ = writeln!(
+        svg,
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" {}>{}</text>",
+        plot.left - 8.0,
+        y + 4.0,
+        FONT,
+        text
+    );
+}
+
+fn x_labels(svg: &mut String, plot: &Plot, first: &Date<Utc>, last: &Date<Utc>) {
+    for (date, x, anchor) in [(first, plot.left, "start"), (last, plot.right, "end")] {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\" {}>{}</text>",
+            x,
+            plot.bottom + 20.0,
+            anchor,
+            FONT,
+            date.naive_utc()
+        );
+    }
+}
+
+/// Makes text safe to put inside an element.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::projects::ProjectBuilder;
+
+    #[test]
+    fn names_are_escaped() {
+        let projects = vec![ProjectBuilder::default().name("R&D <new>").build().unwrap()];
+        let svg = gantt(&projects, &Utc::today(), 600);
+        assert!(svg.contains(">R&amp;D &lt;new&gt;</text>"));
+        assert!(!svg.contains("R&D"))
+    }
+
+    #[test]
+    fn empty_charts_are_still_documents() {
+        for svg in [
+            fan_chart(&[], 400, 300),
+            gantt(&[], &Utc::today(), 400),
+            histogram(&[], 400, 300),
+        ] {
+            assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+            assert!(svg.ends_with("</svg>\n"))
+        }
+    }
+
+    #[test]
+    fn fan_chart_fills_the_plot() {
+        let bands: Vec<Band> = (0..10)
+            .map(|day| Band {
+                date: Utc.ymd(2022, 1, 1) + chrono::Duration::days(day),
+                p10: 0.0,
+                p50: day as f64 * 50.0,
+                p90: day as f64 * 100.0,
+            })
+            .collect();
+        let svg = fan_chart(&bands, 500, 300);
+        assert!(svg.contains("points=\"60.0,260.0 "));
+        assert!(svg.contains(" 480.0,40.0 "));
+        assert!(svg.contains(">900</text>"))
+    }
+}
@@ -0,0 +1,285 @@
+//! Charts drawn with plain characters, for terminals without graphics.
+
+use crate::{
+    projects::Project,
+    stats::{Band, Bin},
+    traits::TimeBound,
+};
+use chrono::prelude::*;
+
+/// Draws a fan chart of the running total over time,
+/// fitting `width` columns and `height` rows of plot.
+///
+/// The P10 to P90 band is drawn with `:`, the median with `*`,
+/// and zero with `-` where nothing else covers it.
+///
+/// ## Example
+/// ```
+/// use chrono::prelude::*;
+/// use hallo::projects::ProjectBuilder;
+/// use hallo::simulation::Simulation;
+/// use hallo::terminal::fan_chart;
+///
+/// let projects = vec![ProjectBuilder::default().start_date(&Utc.ymd(2022, 8, 1)).build().unwrap()];
+/// let result = Simulation::new(&projects)
+///     .start_date(&Utc.ymd(2022, 8, 1))
+///     .end_date(&Utc.ymd(2022, 10, 1))
+///     .run().unwrap();
+/// let chart = fan_chart(&result.fan(), 60, 10);
+/// assert!(chart.lines().all(|line| line.chars().count() <= 60));
+/// assert!(chart.contains('*'))
+/// ```
+pub fn fan_chart(bands: &[Band], width: usize, height: usize) -> String {
+    if bands.is_empty() || height < 2 {
+        return String::new();
+    }
+    let low = bands.iter().map(|b| b.p10).fold(0.0, f64::min);
+    let high = bands.iter().map(|b| b.p90).fold(0.0, f64::max);
+    let span = if high > low { high - low } else { 1.0 };
+    let row_of = |value: f64| ((high - value) / span * (height - 1) as f64).round() as usize;
+
+    let labels: Vec<String> = (0..height)
+        .map(|row| match row {
+            0 => compact(high),
+            _ if row == height - 1 => compact(low),
+            _ if row == row_of(0.0) => "0".into(),
+            _ => String::new(),
+        })
+        .collect();
+    let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+    let columns = width.saturating_sub(label_width + 2).clamp(1, bands.len());
+
+    let mut grid = vec![vec![' '; columns]; height];
+    for (column, cells) in (0..columns).map(|c| (c, c * (bands.len() - 1) / (columns - 1).max(1))) {
+        let band = &bands[cells];
+        for row in grid
+            .iter_mut()
+            .take(row_of(band.p10) + 1)
+            .skip(row_of(band.p90))
+        {
+            row[column] = ':';
+        }
+        grid[row_of(band.p50)][column] = '*';
+        let zero = &mut grid[row_of(0.0)][column];
+        if *zero == ' ' {
+            *zero = '-';
+        }
+    }
+
+    let mut chart = String::new();
+    for (label, row) in labels.iter().zip(grid) {
+        let row: String = row.into_iter().collect();
+        chart += &format!("{:>w$} |{}\n", label, row, w = label_width);
+    }
+    let first = bands[0].date.naive_utc().to_string();
+    let last = bands[bands.len() - 1].date.naive_utc().to_string();
+    chart += &format!("{:>w$} +{}\n", "", "-".repeat(columns), w = label_width);
+    chart += &format!(
+        "{:>w$}  {}{:>gap$}\n",
+        "",
+        first,
+        last,
+        w = label_width,
+        gap = columns.saturating_sub(first.len()).max(last.len() + 1)
+    );
+    chart
+}
+
+/// Draws a histogram with a row per bin, fitting `width` columns.
+///
+/// ## Example
+/// ```
+/// use hallo::stats::Summary;
+/// use hallo::terminal::histogram;
+///
+/// let chart = histogram(&Summary::new(&[0, 0, 0, 10]).histogram(2), 40);
+/// let lines: Vec<_> = chart.lines().collect();
+/// assert_eq!(lines.len(), 2);
+/// assert!(lines[0].ends_with("3"));
+/// assert!(lines[0].matches('#').count() > lines[1].matches('#').count())
+/// ```
+pub fn histogram(bins: &[Bin], width: usize) -> String {
+    let labels: Vec<String> = bins
+        .iter()
+        .map(|bin| format!("{} to {}", compact(bin.from), compact(bin.until)))
+        .collect();
+    let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+    let most = bins.iter().map(|bin| bin.count).max().unwrap_or(0).max(1);
+    let count_width = most.to_string().len();
+    let bar_width = width.saturating_sub(label_width + count_width + 4).max(1);
+
+    let mut chart = String::new();
+    for (label, bin) in labels.iter().zip(bins) {
+        let bar = "#".repeat((bin.count * bar_width).div_ceil(most));
+        chart += &format!(
+            "{:>lw$} | {:<bw$} {}\n",
+            label,
+            bar,
+            bin.count,
+            lw = label_width,
+            bw = bar_width
+        );
+    }
+    chart
+}
+
+/// Draws a bar for every Project, from the start to the end of its Allocation,
+/// fitting `width` columns.
+///
+/// Bars are shaded by the Project's chance of happening, from `░` for
+/// long shots to `█` for sure things, and labelled with its value and currency.
+/// `today` is marked with `|` when it's in range.
+///
+/// ## Example
+/// ```
+/// use chrono::prelude::*;
+/// use hallo::projects::ProjectBuilder;
+/// use hallo::terminal::gantt;
+///
+/// let projects = vec![
+///     ProjectBuilder::default().name("p1").probability(0.5).start_date(&Utc.ymd(2022, 8, 1)).build().unwrap(),
+///     ProjectBuilder::default().name("p2").probability(0.9).start_date(&Utc.ymd(2022, 9, 1)).build().unwrap(),
+/// ];
+/// let chart = gantt(&projects, &Utc.ymd(2022, 8, 15), 60);
+/// let lines: Vec<_> = chart.lines().collect();
+/// assert!(lines[0].ends_with("today"));
+/// assert!(lines[1].starts_with("p1 |▓") && lines[1].ends_with("20k GBP, 50%"));
+/// assert!(lines[2].contains('█') && lines[2].ends_with("20k GBP, 90%"))
+/// ```
+pub fn gantt(projects: &[Project], today: &Date<Utc>, width: usize) -> String {
+    let first = projects.iter().map(|p| *p.start_date()).min();
+    let last = projects.iter().map(|p| *p.end_date()).max();
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return String::new(),
+    };
+    let labels: Vec<String> = projects
+        .iter()
+        .map(|p| {
+            format!(
+                "{} {}, {:.0}%",
+                compact(p.value().amount()),
+                p.currency(),
+                p.probability() * 100.0
+            )
+        })
+        .collect();
+    let name_width = projects
+        .iter()
+        .map(|p| p.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let columns = width.saturating_sub(name_width + label_width + 4).max(2);
+    let days = (last - first).num_days().max(1) as f64;
+    let column_of = |date: &Date<Utc>| {
+        ((*date - first).num_days() as f64 / days * (columns - 1) as f64).round() as usize
+    };
+    let today = (first..=last).contains(today).then(|| column_of(today));
+
+    let mut chart = String::new();
+    if let Some(column) = today {
+        chart += &format!("{:w$}  {}v today\n", "", " ".repeat(column), w = name_width);
+    }
+    for (project, label) in projects.iter().zip(&labels) {
+        let shade = match project.probability() {
+            p if p >= 0.75 => '█',
+            p if p >= 0.5 => '▓',
+            p if p >= 0.25 => '▒',
+            _ => '░',
+        };
+        let (start, end) = (
+            column_of(project.start_date()),
+            column_of(project.end_date()),
+        );
+        let row: String = (0..columns)
+            .map(|column| match column {
+                _ if (start..=end).contains(&column) => shade,
+                _ if Some(column) == today => '|',
+                _ => ' ',
+            })
+            .collect();
+        chart += &format!("{:<w$} |{}| {}\n", project.name, row, label, w = name_width);
+    }
+    let first = first.naive_utc().to_string();
+    let last = last.naive_utc().to_string();
+    chart += &format!("{:w$} +{}+\n", "", "-".repeat(columns), w = name_width);
+    chart += &format!(
+        "{:w$}  {}{:>gap$}\n",
+        "",
+        first,
+        last,
+        w = name_width,
+        gap = columns.saturating_sub(first.len()).max(last.len() + 1)
+    );
+    chart
+}
+
+/// Writes an amount in a few characters, like `-1.2k` or `15M`.
+pub(crate) fn compact(value: f64) -> String {
+    let size = value.abs();
+    let (scaled, suffix) = match size {
+        _ if size >= 1e6 => (value / 1e6, "M"),
+        _ if size >= 1e3 => (value / 1e3, "k"),
+        _ => (value, ""),
+    };
+    if scaled.abs() >= 100.0 || scaled.fract().abs() < 0.05 {
+        format!("{:.0}{}", scaled, suffix)
+    } else {
+        format!("{:.1}{}", scaled, suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn compact_amounts() {
+        assert_eq!(compact(0.0), "0");
+        assert_eq!(compact(950.0), "950");
+        assert_eq!(compact(-1250.0), "-1.2k");
+        assert_eq!(compact(20000.0), "20k");
+        assert_eq!(compact(3_400_000.0), "3.4M")
+    }
+
+    #[test]
+    fn fan_chart_spans_range() {
+        let bands: Vec<Band> = (0..100)
+            .map(|day| Band {
+                date: Utc.ymd(2022, 1, 1) + chrono::Duration::days(day),
+                p10: -(day as f64) * 10.0,
+                p50: 0.0,
+                p90: day as f64 * 10.0,
+            })
+            .collect();
+        let chart = fan_chart(&bands, 40, 9);
+        let lines: Vec<_> = chart.lines().collect();
+        assert_eq!(lines.len(), 9 + 2);
+        assert!(lines[0].starts_with(" 990 |"));
+        assert!(lines[8].starts_with("-990 |"));
+        assert!(lines[4].trim_start().starts_with("0 |*****"));
+        assert!(lines[10].contains("2022-01-01") && lines[10].contains("2022-04-10"))
+    }
+
+    #[test]
+    fn today_is_only_marked_in_range() {
+        let projects = vec![crate::projects::ProjectBuilder::default()
+            .start_date(&Utc.ymd(2022, 1, 1))
+            .build()
+            .unwrap()];
+        let chart = gantt(&projects, &Utc.ymd(2023, 1, 1), 60);
+        assert!(!chart.contains("today"));
+        assert_eq!(chart.lines().count(), 1 + 2);
+        let chart = gantt(&projects, &Utc.ymd(2022, 1, 1), 60);
+        assert!(chart.lines().next().unwrap().ends_with("v today"))
+    }
+
+    #[test]
+    fn empty_inputs() {
+        assert_eq!(fan_chart(&[], 80, 10), "");
+        assert_eq!(histogram(&[], 80), "");
+        assert_eq!(gantt(&[], &Utc::today(), 80), "")
+    }
+}
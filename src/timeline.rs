@@ -0,0 +1,289 @@
+use crate::traits::Contribution;
+use chrono::{prelude::*, Duration};
+
+/// # Granularity
+/// How finely a Timeline is split up.
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
+pub enum Granularity {
+    Day,
+    /// ISO weeks, starting on Monday.
+    Week,
+    /// Calendar months.
+    Month,
+    /// Calendar quarters, starting in January, April, July and October.
+    Quarter,
+}
+
+impl Granularity {
+    /// Returns the first day of the period a date falls in.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::timeline::Granularity;
+    ///
+    /// let date = Utc.ymd(2022, 8, 17);
+    /// assert_eq!(Granularity::Day.period_start(&date), date);
+    /// assert_eq!(Granularity::Week.period_start(&date), Utc.ymd(2022, 8, 15));
+    /// assert_eq!(Granularity::Month.period_start(&date), Utc.ymd(2022, 8, 1));
+    /// assert_eq!(Granularity::Quarter.period_start(&date), Utc.ymd(2022, 7, 1))
+    /// ```
+    pub fn period_start(&self, date: &Date<Utc>) -> Date<Utc> {
+        match self {
+            Granularity::Day => *date,
+            Granularity::Week => {
+                *date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            Granularity::Month => Utc.ymd(date.year(), date.month(), 1),
+            Granularity::Quarter => Utc.ymd(date.year(), date.month0() / 3 * 3 + 1, 1),
+        }
+    }
+
+    /// Returns the first day of the period after the one a date falls in.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::timeline::Granularity;
+    ///
+    /// let date = Utc.ymd(2022, 12, 17);
+    /// assert_eq!(Granularity::Month.next_period_start(&date), Utc.ymd(2023, 1, 1));
+    /// assert_eq!(Granularity::Quarter.next_period_start(&date), Utc.ymd(2023, 1, 1))
+    /// ```
+    pub fn next_period_start(&self, date: &Date<Utc>) -> Date<Utc> {
+        let start = self.period_start(date);
+        let add_months = |months: u32| {
+            let month0 = start.month0() + months;
+            Utc.ymd(start.year() + (month0 / 12) as i32, month0 % 12 + 1, 1)
+        };
+        match self {
+            Granularity::Day => start + Duration::days(1),
+            Granularity::Week => start + Duration::weeks(1),
+            Granularity::Month => add_months(1),
+            Granularity::Quarter => add_months(3),
+        }
+    }
+
+    /// Splits the days from `from` (inclusive) to `until` (exclusive)
+    /// into periods. The first and last periods are cut short
+    /// when the range doesn't line up with the calendar.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::timeline::Granularity;
+    ///
+    /// let periods = Granularity::Month.periods(&Utc.ymd(2022, 8, 15), &Utc.ymd(2022, 10, 1));
+    /// assert_eq!(periods.len(), 2);
+    /// assert!(periods[0].partial);
+    /// assert_eq!(periods[0].days(), 17);
+    /// assert!(!periods[1].partial)
+    /// ```
+    pub fn periods(&self, from: &Date<Utc>, until: &Date<Utc>) -> Vec<Period> {
+        let mut periods = vec![];
+        let mut start_date = *from;
+        while start_date < *until {
+            let natural_end = self.next_period_start(&start_date);
+            let end_date = natural_end.min(*until);
+            periods.push(Period {
+                end_date,
+                partial: self.period_start(&start_date) != start_date || end_date != natural_end,
+                start_date,
+            });
+            start_date = end_date;
+        }
+        periods
+    }
+}
+
+impl std::fmt::Display for Granularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Granularity::Day => write!(f, "day"),
+            Granularity::Week => write!(f, "week"),
+            Granularity::Month => write!(f, "month"),
+            Granularity::Quarter => write!(f, "quarter"),
+        }
+    }
+}
+
+/// # Period
+/// A run of days from `start_date` (inclusive) to `end_date` (exclusive).
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Period {
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
+    pub end_date: Date<Utc>,
+    /// Whether the period was cut short to fit the Timeline's range.
+    pub partial: bool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::date"))]
+    pub start_date: Date<Utc>,
+}
+
+impl Period {
+    /// Returns the number of days in the period.
+    pub fn days(&self) -> i64 {
+        (self.end_date - self.start_date).num_days()
+    }
+
+    /// Returns each day in the period.
+    pub fn dates(&self) -> impl Iterator<Item = Date<Utc>> {
+        let start_date = self.start_date;
+        (0..self.days()).map(move |day| start_date + Duration::days(day))
+    }
+}
+
+/// # Bucket
+/// The total contribution over a single Period, in hundredths.
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bucket {
+    pub period: Period,
+    pub value: i64,
+}
+
+/// # Timeline
+/// Contributions added up period by period.
+/// Values stay in hundredths, and are shown in whole units.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timeline {
+    pub buckets: Vec<Bucket>,
+    pub granularity: Granularity,
+}
+
+impl Timeline {
+    /// Builds a Timeline of anything that contributes,
+    /// from `from` (inclusive) to `until` (exclusive).
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::timeline::{Granularity, Timeline};
+    ///
+    /// let p = ProjectBuilder::default()
+    ///     .value(1400)
+    ///     .start_date(&Utc.ymd(2022, 8, 1))
+    ///     .duration_weeks(2)
+    ///     .build()
+    ///     .unwrap();
+    /// let timeline = Timeline::new(&p, &Utc.ymd(2022, 8, 1), &Utc.ymd(2022, 9, 1), Granularity::Week);
+    /// assert_eq!(timeline.buckets[0].value, 60000);
+    /// assert_eq!(timeline.total(), 140000)
+    /// ```
+    pub fn new<C: Contribution + ?Sized>(
+        item: &C,
+        from: &Date<Utc>,
+        until: &Date<Utc>,
+        granularity: Granularity,
+    ) -> Timeline {
+        let buckets = granularity
+            .periods(from, until)
+            .into_iter()
+            .map(|period| Bucket {
+                period,
+                value: period
+                    .dates()
+                    .map(|date| item.get_contribution_on(&date))
+                    .sum(),
+            })
+            .collect();
+        Timeline {
+            buckets,
+            granularity,
+        }
+    }
+
+    /// Builds a Timeline from daily values, starting on `from`.
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::timeline::{Granularity, Timeline};
+    ///
+    /// let daily = vec![1; 14];
+    /// let timeline = Timeline::from_daily(&Utc.ymd(2022, 8, 25), &daily, Granularity::Month);
+    /// assert_eq!(timeline.buckets[0].value, 7);
+    /// assert_eq!(timeline.buckets[1].value, 7)
+    /// ```
+    pub fn from_daily(from: &Date<Utc>, daily: &[i64], granularity: Granularity) -> Timeline {
+        let until = *from + Duration::days(daily.len() as i64);
+        let buckets = granularity
+            .periods(from, &until)
+            .into_iter()
+            .map(|period| {
+                let offset = (period.start_date - *from).num_days() as usize;
+                let days = period.days() as usize;
+                Bucket {
+                    period,
+                    value: daily[offset..offset + days].iter().sum(),
+                }
+            })
+            .collect();
+        Timeline {
+            buckets,
+            granularity,
+        }
+    }
+
+    /// Returns the total across every period, in hundredths.
+    pub fn total(&self) -> i64 {
+        self.buckets.iter().map(|bucket| bucket.value).sum()
+    }
+}
+
+impl std::fmt::Display for Timeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for bucket in &self.buckets {
+            let marker = if bucket.period.partial { "*" } else { "" };
+            writeln!(
+                f,
+                "{}{}\t{:.2}",
+                bucket.period.start_date.naive_utc(),
+                marker,
+                bucket.value as f64 / 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn periods_cover_range() {
+        let from = Utc.ymd(2022, 2, 14);
+        let until = Utc.ymd(2023, 3, 3);
+        for granularity in [
+            Granularity::Day,
+            Granularity::Week,
+            Granularity::Month,
+            Granularity::Quarter,
+        ] {
+            let periods = granularity.periods(&from, &until);
+            let days: i64 = periods.iter().map(Period::days).sum();
+            assert_eq!(days, (until - from).num_days());
+            assert!(periods.windows(2).all(|w| w[0].end_date == w[1].start_date))
+        }
+    }
+
+    #[test]
+    fn aligned_quarters_are_whole() {
+        let periods = Granularity::Quarter.periods(&Utc.ymd(2022, 1, 1), &Utc.ymd(2023, 1, 1));
+        assert_eq!(periods.len(), 4);
+        assert!(periods.iter().all(|p| !p.partial));
+        assert_eq!(periods[3].start_date, Utc.ymd(2022, 10, 1))
+    }
+
+    #[test]
+    fn empty_range() {
+        let periods = Granularity::Week.periods(&Utc.ymd(2022, 1, 1), &Utc.ymd(2021, 1, 1));
+        assert!(periods.is_empty())
+    }
+}
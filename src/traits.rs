@@ -1,8 +1,201 @@
+use crate::{
+    money::{Currency, MoneyError},
+    simulation::SimulationError,
+};
 use chrono::{Date, Utc};
 use color_eyre::eyre::Result;
+use rand::Rng;
 
+/// # Contribution
+/// Money made (positive) or spent (negative) on a given day,
+/// in hundredths of a unit of its currency.
 pub trait Contribution {
-    fn get_contribution_on(&self, date: &Date<Utc>) -> u32;
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64;
+
+    /// Returns the currency contributions are in, if there are any.
+    /// Fails when parts are in different currencies, as they can't be
+    /// added up without converting them first, in a Portfolio.
+    fn currency(&self) -> Result<Option<Currency>, MoneyError>;
+}
+
+/// Returns the one currency both sides are in, if any.
+fn same_currency(
+    left: Option<Currency>,
+    right: Option<Currency>,
+) -> Result<Option<Currency>, MoneyError> {
+    match (left, right) {
+        (Some(left), Some(right)) if left != right => {
+            Err(MoneyError::CurrencyMismatch(left, right))
+        }
+        _ => Ok(left.or(right)),
+    }
+}
+
+impl<T: Contribution> Contribution for Option<T> {
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        match self {
+            Some(inner) => inner.get_contribution_on(date),
+            None => 0_i64,
+        }
+    }
+
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        match self {
+            Some(inner) => inner.currency(),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: Contribution> Contribution for [T] {
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        self.iter().map(|item| item.get_contribution_on(date)).sum()
+    }
+
+    /// ## Example
+    /// ```
+    /// use hallo::money::{Currency, MoneyError};
+    /// use hallo::projects::ProjectBuilder;
+    /// use hallo::traits::Contribution;
+    ///
+    /// let pounds = ProjectBuilder::default().build().unwrap();
+    /// let euros = ProjectBuilder::default().currency(Currency::EUR).build().unwrap();
+    /// assert_eq!(vec![pounds.clone()].currency(), Ok(Some(Currency::GBP)));
+    /// assert_eq!(
+    ///     vec![pounds, euros].currency(),
+    ///     Err(MoneyError::CurrencyMismatch(Currency::GBP, Currency::EUR))
+    /// )
+    /// ```
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        self.iter().try_fold(None, |currency, item| {
+            same_currency(currency, item.currency()?)
+        })
+    }
+}
+
+impl<T: Contribution> Contribution for Vec<T> {
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        self.as_slice().get_contribution_on(date)
+    }
+
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        self.as_slice().currency()
+    }
+}
+
+impl<T: Contribution + ?Sized> Contribution for &T {
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        (**self).get_contribution_on(date)
+    }
+
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        (**self).currency()
+    }
+}
+
+impl<A: Contribution, B: Contribution> Contribution for (A, B) {
+    fn get_contribution_on(&self, date: &Date<Utc>) -> i64 {
+        self.0.get_contribution_on(date) + self.1.get_contribution_on(date)
+    }
+
+    fn currency(&self) -> Result<Option<Currency>, MoneyError> {
+        same_currency(self.0.currency()?, self.1.currency()?)
+    }
+}
+
+/// # Breakdown
+/// Something made up of named parts that each contribute.
+pub trait Breakdown {
+    /// Returns each part, with its name.
+    fn parts(&self) -> Vec<(&str, &dyn Contribution)>;
+}
+
+impl<T: Breakdown> Breakdown for Option<T> {
+    fn parts(&self) -> Vec<(&str, &dyn Contribution)> {
+        match self {
+            Some(inner) => inner.parts(),
+            None => vec![],
+        }
+    }
+}
+
+impl<T: Breakdown> Breakdown for Vec<T> {
+    fn parts(&self) -> Vec<(&str, &dyn Contribution)> {
+        self.iter().flat_map(Breakdown::parts).collect()
+    }
+}
+
+impl<A: Breakdown, B: Breakdown> Breakdown for (A, B) {
+    fn parts(&self) -> Vec<(&str, &dyn Contribution)> {
+        let mut parts = self.0.parts();
+        parts.extend(self.1.parts());
+        parts
+    }
+}
+
+/// # Sample
+/// Things we can roll the dice on.
+pub trait Sample {
+    /// What a single roll of the dice turns out to be.
+    type Outcome: Contribution;
+
+    /// Rolls the dice once.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Outcome;
+
+    /// Checks the dice can be rolled before a Simulation runs.
+    /// Most things always can.
+    fn check(&self) -> Result<(), SimulationError> {
+        Ok(())
+    }
+}
+
+impl<T: Sample> Sample for [T] {
+    type Outcome = Vec<T::Outcome>;
+
+    fn check(&self) -> Result<(), SimulationError> {
+        self.iter().try_for_each(Sample::check)
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Outcome {
+        self.iter().map(|item| item.sample(rng)).collect()
+    }
+}
+
+impl<T: Sample> Sample for Vec<T> {
+    type Outcome = Vec<T::Outcome>;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Outcome {
+        self.as_slice().sample(rng)
+    }
+
+    fn check(&self) -> Result<(), SimulationError> {
+        self.as_slice().check()
+    }
+}
+
+impl<T: Sample + ?Sized> Sample for &T {
+    type Outcome = T::Outcome;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Outcome {
+        (**self).sample(rng)
+    }
+
+    fn check(&self) -> Result<(), SimulationError> {
+        (**self).check()
+    }
+}
+
+impl<A: Sample, B: Sample> Sample for (A, B) {
+    type Outcome = (A::Outcome, B::Outcome);
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Outcome {
+        (self.0.sample(rng), self.1.sample(rng))
+    }
+
+    fn check(&self) -> Result<(), SimulationError> {
+        self.0.check()?;
+        self.1.check()
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -26,4 +219,23 @@ pub trait TimeBound {
     fn end_date(&self) -> &Date<Utc>;
     fn set_start_date(&mut self, date: &Date<Utc>) -> Result<Date<Utc>, TimeBoundError>;
     fn set_end_date(&mut self, date: &Date<Utc>) -> Result<Date<Utc>, TimeBoundError>;
+
+    /// Checks if any active day, from the day after the start date
+    /// up to and including the end date, falls between `from` (inclusive)
+    /// and `until` (exclusive).
+    ///
+    /// ## Example
+    /// ```
+    /// use chrono::prelude::*;
+    /// use hallo::allocation::Allocation;
+    /// use hallo::traits::TimeBound;
+    ///
+    /// let a = Allocation { start_date: Utc.ymd(2022, 8, 1), end_date: Utc.ymd(2022, 8, 10) };
+    /// assert!(a.overlaps(&Utc.ymd(2022, 8, 10), &Utc.ymd(2022, 9, 1)));
+    /// assert!(!a.overlaps(&Utc.ymd(2022, 7, 1), &Utc.ymd(2022, 8, 2)));
+    /// ```
+    fn overlaps(&self, from: &Date<Utc>, until: &Date<Utc>) -> bool {
+        let first_active = self.start_date().succ();
+        first_active <= *self.end_date() && first_active < *until && from <= self.end_date()
+    }
 }